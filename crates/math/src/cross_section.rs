@@ -3,6 +3,8 @@
 use ndarray::Array1;
 use polars::prelude::*;
 
+use crate::MathError;
+
 /// Cross-sectionally center (demean) a column partitioned by group.
 ///
 /// # Arguments
@@ -18,6 +20,35 @@ pub fn center_xsection(target_col: &str, over_col: &str, standardize: bool) -> E
     if standardize { centered / col(target_col).std(1).over([col(over_col)]) } else { centered }
 }
 
+/// Cross-sectionally center a column on its weighted mean, partitioned by group.
+///
+/// With market cap weights this subtracts the cap-weighted mean, so the cap-weighted
+/// market has zero exposure to the result. When standardizing, the divisor is the
+/// equal-weighted standard deviation. Rows with a null value or weight are
+/// excluded from the mean.
+///
+/// # Arguments
+/// * `target_col` - Column to center
+/// * `weight_col` - Column holding the weights (typically "market_cap")
+/// * `over_col` - Column to partition by (typically "date")
+/// * `standardize` - If true, also divide by standard deviation
+///
+/// # Returns
+/// Polars expression for the centered values.
+pub fn weighted_center_xsection(
+    target_col: &str,
+    weight_col: &str,
+    over_col: &str,
+    standardize: bool,
+) -> Expr {
+    let present = col(target_col).is_not_null().and(col(weight_col).is_not_null());
+    let mean = (col(target_col) * col(weight_col)).sum().over([col(over_col)])
+        / col(weight_col).filter(present).sum().over([col(over_col)]);
+    let centered = col(target_col) - mean;
+
+    if standardize { centered / col(target_col).std(1).over([col(over_col)]) } else { centered }
+}
+
 /// Cross-sectionally normalize a column to [lower, upper] range.
 ///
 /// # Arguments
@@ -92,6 +123,42 @@ impl CenterXSection {
         }
     }
 
+    /// Apply centering around the weighted mean of an array.
+    ///
+    /// The mean is weighted by `weights` (e.g. market caps); the standard
+    /// deviation used when standardizing is equal-weighted. Falls back to the
+    /// simple mean if the weights do not sum to a positive value.
+    ///
+    /// # Errors
+    /// Returns `MathError::DimensionMismatch` if `weights` and `data` have
+    /// different lengths.
+    pub fn apply_weighted(
+        &self,
+        data: &Array1<f64>,
+        weights: &Array1<f64>,
+    ) -> Result<Array1<f64>, MathError> {
+        if weights.len() != data.len() {
+            return Err(MathError::DimensionMismatch {
+                expected: data.len(),
+                actual: weights.len(),
+            });
+        }
+        if data.is_empty() {
+            return Ok(data.clone());
+        }
+
+        let total: f64 = weights.sum();
+        let mean = if total > 0.0 { data.dot(weights) / total } else { data.mean().unwrap_or(0.0) };
+        let centered = data - mean;
+
+        if self.standardize {
+            let std = self.compute_std(data);
+            Ok(if std > 0.0 { &centered / std } else { centered })
+        } else {
+            Ok(centered)
+        }
+    }
+
     fn compute_std(&self, data: &Array1<f64>) -> f64 {
         let n = data.len() as f64;
         if n <= 1.0 {
@@ -173,6 +240,47 @@ mod tests {
         assert_relative_eq!(result.mean().unwrap(), 0.0, epsilon = 1e-10);
     }
 
+    #[test]
+    fn weighted_center_is_cap_neutral() {
+        let data = array![1.0, 2.0, 3.0, 4.0];
+        let caps = array![4.0, 3.0, 2.0, 1.0];
+        let result = CenterXSection::new(true).apply_weighted(&data, &caps).unwrap();
+
+        assert_relative_eq!(result.dot(&caps), 0.0, epsilon = 1e-10);
+        // Equal-weighted std is unchanged by the shift
+        let unweighted = CenterXSection::new(true).apply(&data);
+        assert_relative_eq!(result[1] - result[0], unweighted[1] - unweighted[0], epsilon = 1e-10);
+    }
+
+    #[test]
+    fn weighted_center_length_mismatch_errors() {
+        let result = CenterXSection::new(true).apply_weighted(&array![1.0, 2.0], &array![1.0]);
+        assert!(matches!(result, Err(MathError::DimensionMismatch { expected: 2, actual: 1 })));
+    }
+
+    #[test]
+    fn weighted_center_expression() {
+        let df = df! {
+            "date" => &[1, 1, 1, 2, 2],
+            "value" => &[Some(1.0), Some(2.0), None, Some(5.0), Some(7.0)],
+            "market_cap" => &[3.0, 1.0, 100.0, 1.0, 1.0],
+        }
+        .unwrap();
+
+        let result = df
+            .lazy()
+            .select([weighted_center_xsection("value", "market_cap", "date", false).alias("c")])
+            .collect()
+            .unwrap();
+        let values: Vec<Option<f64>> = result.column("c").unwrap().f64().unwrap().iter().collect();
+
+        // Date 1: cap-weighted mean of 1 and 2 is 1.25; the null row is ignored
+        assert_relative_eq!(values[0].unwrap(), -0.25, epsilon = 1e-12);
+        assert_relative_eq!(values[1].unwrap(), 0.75, epsilon = 1e-12);
+        assert_eq!(values[2], None);
+        assert_relative_eq!(values[3].unwrap(), -1.0, epsilon = 1e-12);
+    }
+
     #[rstest]
     #[case(array![0.0, 25.0, 50.0, 75.0, 100.0], 0.0, 1.0)]
     #[case(array![0.0, 50.0, 100.0], -1.0, 1.0)]
@@ -6,6 +6,7 @@
 mod cross_section;
 pub use cross_section::{
     CenterXSection, NormXSection, center_xsection, norm_xsection, percentiles_xsection,
+    weighted_center_xsection,
 };
 
 mod winsorize;
@@ -15,7 +16,10 @@ mod weights;
 pub use weights::exp_weights;
 
 mod linalg;
-pub use linalg::{ConstrainedWlsResult, WlsResult, constrained_wls, weighted_least_squares};
+pub use linalg::{
+    ConstrainedWlsResult, WlsResult, constrained_wls, constrained_wls_with_countries,
+    orthogonalize, weighted_least_squares,
+};
 
 mod error;
 pub use error::MathError;
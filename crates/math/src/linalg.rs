@@ -22,6 +22,8 @@ pub struct ConstrainedWlsResult {
     pub market_return: f64,
     /// Sector returns.
     pub sector_returns: Array1<f64>,
+    /// Country returns (empty for single-country models).
+    pub country_returns: Array1<f64>,
     /// Style returns.
     pub style_returns: Array1<f64>,
     /// Residual returns.
@@ -95,6 +97,41 @@ pub fn weighted_least_squares(
     Ok(WlsResult { coefficients, residuals, r_squared })
 }
 
+/// Orthogonalize a vector to a set of regressors.
+///
+/// Returns the residual of the weighted regression of `target` on
+/// `regressors`, minimizing `sum(w_i * e_i^2)`. No intercept is added; include
+/// a constant column (or a full set of sector dummies) to remove the mean.
+///
+/// # Arguments
+/// * `target` - Vector to orthogonalize (n,)
+/// * `regressors` - Regressor matrix (n x p); p may be zero
+/// * `weights` - Regression weights (n,), e.g. market caps
+///
+/// # Returns
+/// Residual vector, orthogonal to every regressor under the weighted inner product.
+///
+/// # Errors
+/// Returns error if dimensions mismatch or the regressors are collinear.
+pub fn orthogonalize(
+    target: &Array1<f64>,
+    regressors: &Array2<f64>,
+    weights: &Array1<f64>,
+) -> Result<Array1<f64>, MathError> {
+    if regressors.nrows() != target.len() {
+        return Err(MathError::DimensionMismatch {
+            expected: target.len(),
+            actual: regressors.nrows(),
+        });
+    }
+    if regressors.ncols() == 0 {
+        return Ok(target.clone());
+    }
+
+    let sqrt_weights = weights.mapv(|w| w.max(0.0).sqrt());
+    Ok(weighted_least_squares(target, regressors, &sqrt_weights)?.residuals)
+}
+
 /// Solve a linear system Ax = b using Gaussian elimination with partial pivoting.
 fn solve_linear_system(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>, MathError> {
     let n = a.nrows();
@@ -169,7 +206,8 @@ fn solve_linear_system(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>,
 /// Perform constrained weighted least squares for factor model.
 ///
 /// Implements the constraint that sector factor returns sum to zero,
-/// which makes the market factor identifiable.
+/// which makes the market factor identifiable. Sector exposures may be
+/// fractional (e.g. revenue-weighted splits) provided each row sums to one.
 ///
 /// # Arguments
 /// * `y` - Asset returns (n,)
@@ -187,9 +225,39 @@ pub fn constrained_wls(
     weights: &Array1<f64>,
     sector_matrix: &Array2<f64>,
     style_matrix: &Array2<f64>,
+) -> Result<ConstrainedWlsResult, MathError> {
+    let no_countries = Array2::zeros((y.len(), 0));
+    constrained_wls_with_countries(y, weights, sector_matrix, &no_countries, style_matrix)
+}
+
+/// Perform constrained weighted least squares with a country factor block.
+///
+/// Sector returns and country returns are each constrained to sum to zero,
+/// so that both blocks are identifiable alongside the market factor in a
+/// single regression. An empty country matrix reduces to [`constrained_wls`].
+///
+/// # Arguments
+/// * `y` - Asset returns (n,)
+/// * `weights` - Market cap sqrt weights (n,)
+/// * `sector_matrix` - Sector exposures (n x n_sectors)
+/// * `country_matrix` - Country exposures (n x n_countries), may have zero columns
+/// * `style_matrix` - Style scores (n x n_styles)
+///
+/// # Returns
+/// Constrained WLS result with market, sector, country, style returns and residuals.
+///
+/// # Errors
+/// Returns error if dimensions mismatch or system is singular.
+pub fn constrained_wls_with_countries(
+    y: &Array1<f64>,
+    weights: &Array1<f64>,
+    sector_matrix: &Array2<f64>,
+    country_matrix: &Array2<f64>,
+    style_matrix: &Array2<f64>,
 ) -> Result<ConstrainedWlsResult, MathError> {
     let n = y.len();
     let n_sectors = sector_matrix.ncols();
+    let n_countries = country_matrix.ncols();
     let n_styles = style_matrix.ncols();
 
     // Validate dimensions
@@ -199,6 +267,9 @@ pub fn constrained_wls(
     if sector_matrix.nrows() != n {
         return Err(MathError::DimensionMismatch { expected: n, actual: sector_matrix.nrows() });
     }
+    if country_matrix.nrows() != n {
+        return Err(MathError::DimensionMismatch { expected: n, actual: country_matrix.nrows() });
+    }
     if style_matrix.nrows() != n {
         return Err(MathError::DimensionMismatch { expected: n, actual: style_matrix.nrows() });
     }
@@ -207,11 +278,15 @@ pub fn constrained_wls(
         return Err(MathError::LinearAlgebra("must have at least one sector".to_string()));
     }
 
-    // Build design matrix: [1 | transformed_sectors | styles]
-    // We impose the constraint sum(sector_returns) = 0 by using a change of variables.
-    // Instead of n_sectors columns, we use n_sectors - 1 columns where each column
-    // is the difference from the last sector.
-    let n_cols = 1 + (n_sectors - 1) + n_styles;
+    // Build design matrix: [1 | transformed_sectors | transformed_countries | styles]
+    // We impose the constraints sum(sector_returns) = 0 and sum(country_returns) = 0
+    // by using a change of variables. Instead of n_sectors columns, we use n_sectors - 1
+    // columns where each column is the difference from the last sector (likewise for
+    // countries).
+    let country_offset = 1 + (n_sectors - 1);
+    let n_country_cols = n_countries.saturating_sub(1);
+    let style_offset = country_offset + n_country_cols;
+    let n_cols = style_offset + n_styles;
     let mut x = Array2::zeros((n, n_cols));
 
     // Market column (all ones)
@@ -226,10 +301,18 @@ pub fn constrained_wls(
         }
     }
 
+    // Country columns (difference from last country)
+    for i in 0..n {
+        for j in 0..n_country_cols {
+            x[[i, country_offset + j]] =
+                country_matrix[[i, j]] - country_matrix[[i, n_countries - 1]];
+        }
+    }
+
     // Style columns
     for i in 0..n {
         for j in 0..n_styles {
-            x[[i, 1 + (n_sectors - 1) + j]] = style_matrix[[i, j]];
+            x[[i, style_offset + j]] = style_matrix[[i, j]];
         }
     }
 
@@ -247,11 +330,21 @@ pub fn constrained_wls(
     // Last sector return is negative sum of others (constraint: sum = 0)
     sector_returns[n_sectors - 1] = -sector_returns.slice(s![..(n_sectors - 1)]).sum();
 
-    let style_returns = result.coefficients.slice(s![(1 + n_sectors - 1)..]).to_owned();
+    // Reconstruct country returns with constraint
+    let mut country_returns = Array1::zeros(n_countries);
+    if n_countries > 0 {
+        for j in 0..n_country_cols {
+            country_returns[j] = result.coefficients[country_offset + j];
+        }
+        country_returns[n_countries - 1] = -country_returns.slice(s![..n_country_cols]).sum();
+    }
+
+    let style_returns = result.coefficients.slice(s![style_offset..]).to_owned();
 
     Ok(ConstrainedWlsResult {
         market_return,
         sector_returns,
+        country_returns,
         style_returns,
         residuals: result.residuals,
     })
@@ -264,6 +357,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn orthogonalize_removes_projection() {
+        let target = array![1.0, 2.0, 0.5, 3.0, -1.0];
+        let size = array![0.3, -1.2, 0.8, 1.5, -0.4];
+        let regressors = Array2::from_shape_fn((5, 2), |(i, j)| if j == 0 { 1.0 } else { size[i] });
+        let weights = array![1.0, 4.0, 2.0, 3.0, 1.0];
+
+        let resid = orthogonalize(&target, &regressors, &weights).unwrap();
+
+        for j in 0..2 {
+            let inner: f64 = (0..5).map(|i| weights[i] * resid[i] * regressors[[i, j]]).sum();
+            assert_relative_eq!(inner, 0.0, epsilon = 1e-10);
+        }
+        assert_eq!(orthogonalize(&target, &Array2::zeros((5, 0)), &weights).unwrap(), target);
+    }
+
     #[test]
     fn wls_simple_regression() {
         let y = array![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -317,6 +426,75 @@ mod tests {
         assert_relative_eq!(sector_sum, 0.0, epsilon = 1e-10);
     }
 
+    #[test]
+    fn constrained_wls_fractional_sectors() {
+        // Noise-free returns generated from known factor returns, with two
+        // assets split across sectors.
+        let sectors = Array2::from_shape_vec(
+            (6, 2),
+            vec![1.0, 0.0, 1.0, 0.0, 0.6, 0.4, 0.0, 1.0, 0.0, 1.0, 0.25, 0.75],
+        )
+        .unwrap();
+        let styles = Array2::from_shape_vec((6, 1), vec![0.5, -0.3, 0.2, 0.4, -0.6, 0.1]).unwrap();
+        let (market, sector_rets, style_ret) = (0.01, [0.004, -0.004], 0.02);
+        let y: Array1<f64> = (0..6)
+            .map(|i| {
+                market
+                    + sectors[[i, 0]] * sector_rets[0]
+                    + sectors[[i, 1]] * sector_rets[1]
+                    + styles[[i, 0]] * style_ret
+            })
+            .collect();
+        let weights = array![1.0, 2.0, 1.5, 1.0, 0.5, 1.0];
+
+        let result = constrained_wls(&y, &weights, &sectors, &styles).unwrap();
+
+        assert_relative_eq!(result.market_return, market, epsilon = 1e-10);
+        assert_relative_eq!(result.sector_returns[0], sector_rets[0], epsilon = 1e-10);
+        assert_relative_eq!(result.sector_returns[1], sector_rets[1], epsilon = 1e-10);
+        assert_relative_eq!(result.style_returns[0], style_ret, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn constrained_wls_country_block() {
+        // Two sectors crossed with two countries, noise-free returns
+        let sectors = Array2::from_shape_vec(
+            (8, 2),
+            vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0],
+        )
+        .unwrap();
+        let countries = Array2::from_shape_vec(
+            (8, 2),
+            vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0],
+        )
+        .unwrap();
+        let styles =
+            Array2::from_shape_vec((8, 1), vec![0.5, -0.3, 0.2, 0.4, -0.6, 0.1, 0.3, -0.2])
+                .unwrap();
+        let (market, sector_rets, country_rets, style_ret) =
+            (0.01, [0.004, -0.004], [-0.003, 0.003], 0.02);
+        let y: Array1<f64> = (0..8)
+            .map(|i| {
+                market
+                    + sectors[[i, 0]] * sector_rets[0]
+                    + sectors[[i, 1]] * sector_rets[1]
+                    + countries[[i, 0]] * country_rets[0]
+                    + countries[[i, 1]] * country_rets[1]
+                    + styles[[i, 0]] * style_ret
+            })
+            .collect();
+        let weights = Array1::ones(8);
+
+        let result =
+            constrained_wls_with_countries(&y, &weights, &sectors, &countries, &styles).unwrap();
+
+        assert_relative_eq!(result.market_return, market, epsilon = 1e-10);
+        assert_relative_eq!(result.sector_returns[0], sector_rets[0], epsilon = 1e-10);
+        assert_relative_eq!(result.country_returns[0], country_rets[0], epsilon = 1e-10);
+        assert_relative_eq!(result.country_returns.sum(), 0.0, epsilon = 1e-10);
+        assert_relative_eq!(result.style_returns[0], style_ret, epsilon = 1e-10);
+    }
+
     #[test]
     fn constrained_wls_dimensions() {
         // Need more observations than unknowns: 1 market + 2 sectors + 1 style = 4 unknowns
@@ -335,6 +513,7 @@ mod tests {
         let result = constrained_wls(&y, &weights, &sectors, &styles).unwrap();
 
         assert_eq!(result.sector_returns.len(), 2);
+        assert!(result.country_returns.is_empty());
         assert_eq!(result.style_returns.len(), 1);
         assert_eq!(result.residuals.len(), 6);
     }
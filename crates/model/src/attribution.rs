@@ -16,6 +16,61 @@ fn extract_factor_return_sum(df: &DataFrame) -> f64 {
         .unwrap_or(0.0)
 }
 
+/// Helper to compute a factor's contribution from dated exposures.
+///
+/// The contribution is the sum over dates of exposure times factor return, so
+/// time-varying and fractional exposures are attributed correctly. The reported
+/// exposure is the average over the period and the factor return is cumulative
+/// over the dates the stock was exposed.
+fn dated_contribution(
+    exposures: &DataFrame,
+    factor_returns: &DataFrame,
+    factor: &str,
+) -> Result<FactorContribution, ModelError> {
+    let factor_rets = factor_returns
+        .clone()
+        .lazy()
+        .filter(col("factor").eq(lit(factor)))
+        .select([col("date"), col("factor_return")]);
+
+    let summary = exposures
+        .clone()
+        .lazy()
+        .select([col("date"), col(factor).alias("exposure")])
+        .join(factor_rets, [col("date")], [col("date")], JoinArgs::new(JoinType::Left))
+        .select([
+            col("exposure").mean().alias("exposure"),
+            col("factor_return").sum().alias("factor_return"),
+            (col("exposure") * col("factor_return")).sum().alias("contribution"),
+        ])
+        .collect()?;
+
+    let value = |name: &str| -> Result<f64, ModelError> {
+        Ok(summary.column(name)?.f64()?.get(0).unwrap_or(0.0))
+    };
+
+    Ok(FactorContribution {
+        factor: factor.to_string(),
+        exposure: value("exposure")?,
+        factor_return: value("factor_return")?,
+        contribution: value("contribution")?,
+    })
+}
+
+/// Contributions of every float exposure column selected by `is_factor`.
+fn contributions(
+    exposures: &DataFrame,
+    factor_returns: &DataFrame,
+    is_factor: impl Fn(&str) -> bool,
+) -> Result<Vec<FactorContribution>, ModelError> {
+    exposures
+        .get_columns()
+        .iter()
+        .filter(|c| is_factor(c.name()) && c.dtype().is_float())
+        .map(|c| dated_contribution(exposures, factor_returns, c.name()))
+        .collect()
+}
+
 /// Factor contribution to stock returns.
 #[derive(Debug, Clone)]
 pub struct FactorContribution {
@@ -44,6 +99,8 @@ pub struct AttributionResult {
     pub market_contribution: f64,
     /// Sector contributions.
     pub sector_contributions: Vec<FactorContribution>,
+    /// Country contributions (empty for single-country models).
+    pub country_contributions: Vec<FactorContribution>,
     /// Style factor contributions.
     pub style_contributions: Vec<FactorContribution>,
     /// Idiosyncratic (residual) contribution.
@@ -58,6 +115,7 @@ impl AttributionResult {
     pub fn factor_explained_return(&self) -> f64 {
         self.market_contribution
             + self.sector_contributions.iter().map(|c| c.contribution).sum::<f64>()
+            + self.country_contributions.iter().map(|c| c.contribution).sum::<f64>()
             + self.style_contributions.iter().map(|c| c.contribution).sum::<f64>()
     }
 
@@ -88,12 +146,12 @@ impl AttributionResult {
             self.market_contribution * 100.0
         );
 
-        // Sectors
-        for contrib in &self.sector_contributions {
+        // Sectors and countries
+        for contrib in self.sector_contributions.iter().chain(&self.country_contributions) {
             if contrib.exposure.abs() > 0.001 {
                 println!(
                     "{:<20} {:>12.3} {:>13.2}% {:>13.2}%",
-                    contrib.factor.replace("sector_", ""),
+                    contrib.factor.replace("sector_", "").replace("country_", ""),
                     contrib.exposure,
                     contrib.factor_return * 100.0,
                     contrib.contribution * 100.0
@@ -151,7 +209,8 @@ impl AttributionResult {
 /// * `factor_returns` - DataFrame with columns: date, factor, factor_return
 /// * `residuals` - DataFrame with columns: date, symbol, residual_return
 /// * `style_scores` - DataFrame with columns: date, symbol, *_score
-/// * `sector_df` - DataFrame with columns: date, symbol, sector_*
+/// * `sector_df` - DataFrame with columns: date, symbol, sector_*, and
+///   country_* for multi-country models
 ///
 /// # Returns
 /// Attribution result showing factor contributions.
@@ -210,73 +269,13 @@ pub fn compute_attribution(
     let residual_col = symbol_residuals.column("residual_return")?.f64()?;
     let idio_return: f64 = residual_col.iter().flatten().sum();
 
-    // Get average style exposures for this stock
-    let style_cols: Vec<String> = symbol_styles
-        .get_column_names()
-        .iter()
-        .filter(|c| c.ends_with("_score"))
-        .map(|s| s.to_string())
-        .collect();
-
-    let mut style_contributions = Vec::new();
-    for style_col in &style_cols {
-        if let Ok(col_data) = symbol_styles.column(style_col)
-            && let Ok(f64_col) = col_data.f64()
-        {
-            let avg_exposure = f64_col.mean().unwrap_or(0.0);
-
-            // Get cumulative factor return for this style
-            let style_factor_returns = factor_returns
-                .clone()
-                .lazy()
-                .filter(col("factor").eq(lit(style_col.as_str())))
-                .select([col("factor_return")])
-                .collect()?;
-
-            let factor_ret = extract_factor_return_sum(&style_factor_returns);
-
-            style_contributions.push(FactorContribution {
-                factor: style_col.clone(),
-                exposure: avg_exposure,
-                factor_return: factor_ret,
-                contribution: avg_exposure * factor_ret,
-            });
-        }
-    }
-
-    // Get sector exposures
-    let sector_cols: Vec<String> = symbol_sectors
-        .get_column_names()
-        .iter()
-        .filter(|c| c.starts_with("sector_"))
-        .map(|s| s.to_string())
-        .collect();
-
-    let mut sector_contributions = Vec::new();
-    for sector_col in &sector_cols {
-        if let Ok(col_data) = symbol_sectors.column(sector_col)
-            && let Ok(f64_col) = col_data.f64()
-        {
-            let avg_exposure = f64_col.mean().unwrap_or(0.0);
-
-            // Get cumulative factor return for this sector
-            let sector_factor_returns = factor_returns
-                .clone()
-                .lazy()
-                .filter(col("factor").eq(lit(sector_col.as_str())))
-                .select([col("factor_return")])
-                .collect()?;
-
-            let factor_ret = extract_factor_return_sum(&sector_factor_returns);
-
-            sector_contributions.push(FactorContribution {
-                factor: sector_col.clone(),
-                exposure: avg_exposure,
-                factor_return: factor_ret,
-                contribution: avg_exposure * factor_ret,
-            });
-        }
-    }
+    // Style exposures, and sector and country exposures from the sector frame
+    let style_contributions =
+        contributions(&symbol_styles, factor_returns, |c| c.ends_with("_score"))?;
+    let sector_contributions =
+        contributions(&symbol_sectors, factor_returns, |c| c.starts_with("sector_"))?;
+    let country_contributions =
+        contributions(&symbol_sectors, factor_returns, |c| c.starts_with("country_"))?;
 
     // Get market contribution
     let market_returns = factor_returns
@@ -291,6 +290,7 @@ pub fn compute_attribution(
     // Compute total return
     let factor_explained = market_contribution
         + sector_contributions.iter().map(|c| c.contribution).sum::<f64>()
+        + country_contributions.iter().map(|c| c.contribution).sum::<f64>()
         + style_contributions.iter().map(|c| c.contribution).sum::<f64>();
     let total_return = factor_explained + idio_return;
 
@@ -308,6 +308,7 @@ pub fn compute_attribution(
         total_return,
         market_contribution,
         sector_contributions,
+        country_contributions,
         style_contributions,
         idiosyncratic_contribution: idio_return,
         r_squared,
@@ -316,6 +317,8 @@ pub fn compute_attribution(
 
 #[cfg(test)]
 mod tests {
+    use toraniko_primitives::Date;
+
     use super::*;
 
     #[test]
@@ -330,6 +333,90 @@ mod tests {
         assert!((contrib.contribution - 0.03).abs() < 1e-10);
     }
 
+    #[test]
+    fn attribution_fractional_sectors() {
+        let d1 = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let d2 = Date::from_ymd_opt(2024, 1, 3).unwrap();
+
+        let factor_returns = df! {
+            "date" => &[d1, d1, d1, d2, d2, d2],
+            "factor" => &["market", "sector_A", "sector_B", "market", "sector_A", "sector_B"],
+            "factor_return" => &[0.01, 0.02, -0.02, 0.005, -0.01, 0.01],
+        }
+        .unwrap();
+        let residuals = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+            "residual_return" => &[0.001, -0.002],
+        }
+        .unwrap();
+        let styles = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+        }
+        .unwrap();
+        // Split 60/40 on the first date, fully in A on the second
+        let sectors = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+            "sector_A" => &[0.6, 1.0],
+            "sector_B" => &[0.4, 0.0],
+        }
+        .unwrap();
+
+        let result =
+            compute_attribution("X", &factor_returns, &residuals, &styles, &sectors).unwrap();
+
+        assert_eq!(result.start_date, "2024-01-02");
+        let a = &result.sector_contributions[0];
+        assert!((a.exposure - 0.8).abs() < 1e-12);
+        assert!((a.contribution - (0.6 * 0.02 + 1.0 * -0.01)).abs() < 1e-12);
+        let b = &result.sector_contributions[1];
+        assert!((b.contribution - 0.4 * -0.02).abs() < 1e-12);
+        assert!((result.total_return - (result.factor_explained_return() - 0.001)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn attribution_country_factors() {
+        let d1 = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let d2 = Date::from_ymd_opt(2024, 1, 3).unwrap();
+
+        let factor_returns = df! {
+            "date" => &[d1, d1, d1, d2, d2, d2],
+            "factor" => &["market", "sector_A", "country_US", "market", "sector_A", "country_US"],
+            "factor_return" => &[0.01, 0.02, 0.003, 0.005, -0.01, -0.004],
+        }
+        .unwrap();
+        let residuals = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+            "residual_return" => &[0.001, -0.002],
+        }
+        .unwrap();
+        let styles = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+        }
+        .unwrap();
+        let sectors = df! {
+            "date" => &[d1, d2],
+            "symbol" => &["X", "X"],
+            "sector_A" => &[1.0, 1.0],
+            "country_US" => &[1.0, 1.0],
+        }
+        .unwrap();
+
+        let result =
+            compute_attribution("X", &factor_returns, &residuals, &styles, &sectors).unwrap();
+
+        assert_eq!(result.sector_contributions.len(), 1);
+        let us = &result.country_contributions[0];
+        assert_eq!(us.factor, "country_US");
+        assert!((us.contribution - (0.003 - 0.004)).abs() < 1e-12);
+        let expected = 0.015 + (0.02 - 0.01) + (0.003 - 0.004) - 0.001;
+        assert!((result.total_return - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn attribution_result_factor_explained() {
         let result = AttributionResult {
@@ -344,6 +431,7 @@ mod tests {
                 factor_return: 0.02,
                 contribution: 0.02,
             }],
+            country_contributions: Vec::new(),
             style_contributions: vec![FactorContribution {
                 factor: "mom_score".to_string(),
                 exposure: 0.5,
@@ -0,0 +1,135 @@
+//! Currency conversion for multi-country models.
+//!
+//! Global universes are estimated in a single base currency: local-currency
+//! asset returns are converted with FX returns before the cross-sectional
+//! regression, so the resulting factor returns are base-currency returns.
+
+use polars::prelude::*;
+
+use crate::ModelError;
+
+/// Convert local-currency asset returns to base-currency returns.
+///
+/// Computes `(1 + r_local) * (1 + r_fx) - 1`, where `r_fx` is the return of the
+/// asset's local currency measured in the base currency. Assets quoted in
+/// `base_currency` need no FX row and are left unchanged; any other asset must
+/// have an FX return for its currency on every date.
+///
+/// Market caps used for weighting must already be expressed in a common currency.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | `currency_col` | asset_returns |
+/// * `fx_df` - LazyFrame with | date | `currency_col` | fx_return |
+/// * `currency_col` - Column holding the currency code
+/// * `base_currency` - Currency code the returns are converted into
+///
+/// # Returns
+/// LazyFrame with `asset_returns` in the base currency and all other input columns unchanged.
+///
+/// # Errors
+/// Returns `ModelError::MissingFxRates` listing the first missing `(date, currency)`
+/// pair if a non-base currency has no FX return on some date, or
+/// `ModelError::Polars` if the inputs cannot be evaluated.
+pub fn to_base_currency(
+    returns_df: LazyFrame,
+    fx_df: LazyFrame,
+    currency_col: &str,
+    base_currency: &str,
+) -> Result<LazyFrame, ModelError> {
+    let fx = fx_df.select([col("date"), col(currency_col), col("fx_return").alias("_fx_return")]);
+
+    let joined = returns_df.join(
+        fx,
+        [col("date"), col(currency_col)],
+        [col("date"), col(currency_col)],
+        JoinArgs::new(JoinType::Left),
+    );
+
+    let missing = joined
+        .clone()
+        .filter(col("_fx_return").is_null().and(col(currency_col).neq(lit(base_currency))))
+        .select([col("date"), col(currency_col)])
+        .unique_stable(None, UniqueKeepStrategy::First)
+        .sort(["date", currency_col], SortMultipleOptions::default())
+        .collect()?;
+    if missing.height() > 0 {
+        return Err(ModelError::MissingFxRates(format!(
+            "{} date, currency pairs (first: {} on {})",
+            missing.height(),
+            missing.column(currency_col)?.get(0)?,
+            missing.column("date")?.get(0)?,
+        )));
+    }
+
+    Ok(joined
+        .with_column(
+            ((lit(1.0) + col("asset_returns"))
+                * (lit(1.0) + col("_fx_return").fill_null(lit(0.0)))
+                - lit(1.0))
+            .alias("asset_returns"),
+        )
+        .select([col("*").exclude(["_fx_return"])]))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn converts_local_returns() {
+        let returns = df! {
+            "date" => &[1, 1, 2],
+            "symbol" => &["A", "B", "A"],
+            "currency" => &["EUR", "USD", "EUR"],
+            "asset_returns" => &[0.01, 0.02, -0.01],
+        }
+        .unwrap()
+        .lazy();
+        let fx = df! {
+            "date" => &[1, 2],
+            "currency" => &["EUR", "EUR"],
+            "fx_return" => &[0.005, 0.002],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = to_base_currency(returns, fx, "currency", "USD")
+            .unwrap()
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let values = result.column("asset_returns").unwrap().f64().unwrap();
+
+        assert_relative_eq!(values.get(0).unwrap(), 1.01 * 1.005 - 1.0, epsilon = 1e-12);
+        assert_relative_eq!(values.get(1).unwrap(), 0.02, epsilon = 1e-12);
+        assert_relative_eq!(values.get(2).unwrap(), 0.99 * 1.002 - 1.0, epsilon = 1e-12);
+        assert!(result.column("_fx_return").is_err());
+    }
+
+    #[test]
+    fn missing_fx_rate_errors() {
+        let returns = df! {
+            "date" => &[1, 2, 2],
+            "symbol" => &["A", "A", "B"],
+            "currency" => &["EUR", "EUR", "USD"],
+            "asset_returns" => &[0.01, -0.01, 0.02],
+        }
+        .unwrap()
+        .lazy();
+        let fx = df! {
+            "date" => &[1],
+            "currency" => &["EUR"],
+            "fx_return" => &[0.005],
+        }
+        .unwrap()
+        .lazy();
+
+        let Err(err) = to_base_currency(returns, fx, "currency", "USD") else {
+            panic!("expected missing FX rates to error");
+        };
+        assert!(matches!(err, ModelError::MissingFxRates(_)));
+        assert!(err.to_string().contains("EUR"));
+    }
+}
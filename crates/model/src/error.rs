@@ -3,6 +3,8 @@
 use toraniko_math::MathError;
 use toraniko_traits::EstimatorError;
 
+use crate::ValidationReport;
+
 /// Errors that can occur during factor model estimation.
 #[derive(Debug, thiserror::Error)]
 pub enum ModelError {
@@ -33,13 +35,32 @@ pub enum ModelError {
     /// Dimension mismatch.
     #[error("dimension mismatch: {0}")]
     DimensionMismatch(String),
+
+    /// Input frames that do not match the expected schema.
+    #[error("invalid input data: {0}")]
+    InvalidInput(ValidationReport),
+
+    /// Local currencies without an FX return.
+    #[error("missing FX returns: {0}")]
+    MissingFxRates(String),
+
+    /// Country factor with too few names to be estimated.
+    #[error("country column {column} has {names} names, need at least {required}")]
+    UnderpopulatedCountry {
+        /// Index of the country column.
+        column: usize,
+        /// Number of names exposed to the country.
+        names: usize,
+        /// Minimum number of names required.
+        required: usize,
+    },
 }
 
 impl ModelError {
     /// Returns whether this error is recoverable.
     #[must_use]
     pub const fn is_recoverable(&self) -> bool {
-        matches!(self, Self::NoDataForDate(_))
+        matches!(self, Self::NoDataForDate(_) | Self::UnderpopulatedCountry { .. })
     }
 }
 
@@ -58,6 +79,9 @@ mod tests {
         let err = ModelError::NoDataForDate("2024-01-01".to_string());
         assert!(err.is_recoverable());
 
+        let err = ModelError::UnderpopulatedCountry { column: 0, names: 1, required: 3 };
+        assert!(err.is_recoverable());
+
         let err = ModelError::MissingColumn("test".to_string());
         assert!(!err.is_recoverable());
     }
@@ -0,0 +1,153 @@
+//! Sector exposure construction and validation.
+//!
+//! Sector exposures need not be one-hot: a conglomerate can be split across
+//! several industries (e.g. by revenue share), as long as each asset's
+//! memberships are non-negative and sum to one.
+
+use ndarray::Array2;
+use polars::prelude::*;
+
+use crate::ModelError;
+
+/// Tolerance used when checking that sector memberships sum to one.
+pub const MEMBERSHIP_TOLERANCE: f64 = 1e-6;
+
+/// Returns the rows of an exposure matrix that are not valid sector memberships.
+///
+/// A row is valid when every exposure is finite and non-negative and the row
+/// sums to one within `tolerance`.
+#[must_use]
+pub fn invalid_membership_rows(exposures: &Array2<f64>, tolerance: f64) -> Vec<usize> {
+    exposures
+        .rows()
+        .into_iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            row.iter().any(|x| !x.is_finite() || *x < 0.0) || (row.sum() - 1.0).abs() > tolerance
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Build fractional sector exposures from segment-level memberships.
+///
+/// Each input row assigns a weight (e.g. revenue share) of an asset to a
+/// sector; multiple rows per asset and date describe a multi-segment company.
+/// Weights for the same sector are summed.
+///
+/// # Arguments
+/// * `df` - LazyFrame with | date | symbol | `sector_col` | `weight_col` |
+/// * `sector_col` - Column holding the sector name
+/// * `weight_col` - Column holding the membership weight
+///
+/// # Returns
+/// DataFrame with | date | symbol | sector_* | columns, sorted by date and symbol.
+///
+/// # Errors
+/// Returns `ModelError::InvalidConfig` if any asset's memberships are negative
+/// or do not sum to one.
+pub fn fractional_sector_exposures(
+    df: LazyFrame,
+    sector_col: &str,
+    weight_col: &str,
+) -> Result<DataFrame, ModelError> {
+    let df = df
+        .select([
+            col("date"),
+            col("symbol"),
+            col(sector_col),
+            col(weight_col).cast(DataType::Float64),
+        ])
+        .collect()?;
+
+    let mut sectors: Vec<String> =
+        df.column(sector_col)?.str()?.into_iter().flatten().map(str::to_string).collect();
+    sectors.sort_unstable();
+    sectors.dedup();
+
+    let aggs: Vec<Expr> = sectors
+        .iter()
+        .map(|s| {
+            col(weight_col)
+                .filter(col(sector_col).eq(lit(s.as_str())))
+                .sum()
+                .alias(format!("sector_{s}"))
+        })
+        .collect();
+
+    let wide = df
+        .lazy()
+        .group_by([col("date"), col("symbol")])
+        .agg(aggs)
+        .sort(["date", "symbol"], SortMultipleOptions::default())
+        .collect()?;
+
+    let mut exposures = Array2::zeros((wide.height(), sectors.len()));
+    for (j, s) in sectors.iter().enumerate() {
+        let values = wide.column(&format!("sector_{s}"))?.f64()?;
+        for (i, v) in values.iter().enumerate() {
+            exposures[[i, j]] = v.unwrap_or(0.0);
+        }
+    }
+
+    let invalid = invalid_membership_rows(&exposures, MEMBERSHIP_TOLERANCE);
+    if let Some(&first) = invalid.first() {
+        let symbol = wide.column("symbol")?.str()?.get(first).unwrap_or("").to_string();
+        return Err(ModelError::InvalidConfig(format!(
+            "{} asset-dates have sector memberships that are negative or do not sum to one \
+             (first: {symbol}, sum {:.6})",
+            invalid.len(),
+            exposures.row(first).sum()
+        )));
+    }
+
+    Ok(wide)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn invalid_rows_detected() {
+        let exposures = array![[1.0, 0.0], [0.6, 0.4], [0.5, 0.4], [1.2, -0.2], [0.0, 0.0]];
+        assert_eq!(invalid_membership_rows(&exposures, MEMBERSHIP_TOLERANCE), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn fractional_exposures_pivot() {
+        let df = df! {
+            "date" => &[1, 1, 1, 1],
+            "symbol" => &["A", "A", "B", "C"],
+            "segment" => &["Tech", "Finance", "Tech", "Finance"],
+            "revenue_share" => &[0.7, 0.3, 1.0, 1.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = fractional_sector_exposures(df, "segment", "revenue_share").unwrap();
+
+        assert_eq!(result.height(), 3);
+        let tech = result.column("sector_Tech").unwrap().f64().unwrap();
+        let finance = result.column("sector_Finance").unwrap().f64().unwrap();
+        assert_eq!(tech.get(0), Some(0.7));
+        assert_eq!(finance.get(0), Some(0.3));
+        assert_eq!(finance.get(1), Some(0.0));
+    }
+
+    #[test]
+    fn fractional_exposures_must_sum_to_one() {
+        let df = df! {
+            "date" => &[1, 1],
+            "symbol" => &["A", "A"],
+            "segment" => &["Tech", "Finance"],
+            "revenue_share" => &[0.7, 0.2],
+        }
+        .unwrap()
+        .lazy();
+
+        assert!(fractional_sector_exposures(df, "segment", "revenue_share").is_err());
+    }
+}
@@ -1,24 +1,60 @@
 //! Factor returns estimation.
 
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, Axis};
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
 use toraniko_primitives::Date;
 use toraniko_traits::{EstimatorError, FactorEstimator, ReturnsEstimator};
 
-use crate::{ModelError, WlsConfig, WlsFactorEstimator};
+use crate::{
+    EstimationFrequency, EstimationUniverse, MEMBERSHIP_TOLERANCE, ModelError,
+    StyleOrthogonalization, WlsConfig, WlsFactorEstimator, invalid_membership_rows,
+    orthogonalize::{ResolvedRule, orthogonalize_styles, resolve_rules},
+};
+
+/// Name of the temporary estimation universe membership column.
+const ESTU_COLUMN: &str = "_estu";
 
 /// Configuration for factor returns estimation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EstimatorConfig {
     /// Winsorization percentile for returns (None to disable).
     pub winsor_factor: Option<f64>,
-    /// Whether to orthogonalize style returns to sector residuals.
+    /// Whether to orthogonalize style scores to sectors before the regression.
     pub residualize_styles: bool,
+    /// Style-to-style orthogonalization rules, applied in order on every date
+    /// before the regression (e.g. residual volatility to beta and size).
+    pub style_orthogonalization: Vec<StyleOrthogonalization>,
+    /// Minimum number of names for a country factor to be estimated on a date.
+    ///
+    /// [`ReturnsEstimator::estimate`] drops countries below the minimum from that
+    /// date's regression, so their assets carry no country exposure and no
+    /// error is raised. [`FactorReturnsEstimator::estimate_single_with_countries`]
+    /// takes its country columns as given and returns
+    /// `ModelError::UnderpopulatedCountry` instead.
+    pub min_country_names: usize,
+    /// Assets used to fit factor returns on each date.
+    ///
+    /// Residual returns are reported for every asset in the joined inputs (the
+    /// coverage universe), using the factor returns fit on this subset. Members
+    /// keep their regression residuals, so their residuals match a model fit on
+    /// the subset alone.
+    pub estimation_universe: EstimationUniverse,
+    /// Regression frequency. Weekly and monthly models compound the daily
+    /// returns over each period and sample exposures at the period start.
+    pub frequency: EstimationFrequency,
 }
 
 impl Default for EstimatorConfig {
     fn default() -> Self {
-        Self { winsor_factor: Some(0.05), residualize_styles: true }
+        Self {
+            winsor_factor: Some(0.05),
+            residualize_styles: true,
+            style_orthogonalization: Vec::new(),
+            min_country_names: 1,
+            estimation_universe: EstimationUniverse::All,
+            frequency: EstimationFrequency::Daily,
+        }
     }
 }
 
@@ -26,6 +62,14 @@ impl Default for EstimatorConfig {
 ///
 /// Estimates market, sector, and style factor returns using weighted
 /// least squares with market cap weighting and sector sum constraint.
+/// Optional `country_*` exposure columns add a country factor block with
+/// its own sum-to-zero constraint.
+///
+/// When an estimation universe is configured, the regression runs on that
+/// subset. Estimation universe assets report the regression residuals (on
+/// winsorized returns, when enabled) whatever the universe setting, and the
+/// remaining coverage assets report their raw return less the fitted factor
+/// return.
 #[derive(Debug, Clone)]
 pub struct FactorReturnsEstimator {
     config: EstimatorConfig,
@@ -42,10 +86,9 @@ impl FactorReturnsEstimator {
     /// Create a new estimator with custom configuration.
     #[must_use]
     pub fn with_config(config: EstimatorConfig) -> Self {
-        let wls_config = WlsConfig {
-            winsor_factor: config.winsor_factor,
-            residualize_styles: config.residualize_styles,
-        };
+        // Styles are orthogonalized here, before they reach the regression
+        let wls_config =
+            WlsConfig { winsor_factor: config.winsor_factor, residualize_styles: false };
         Self { config, wls: WlsFactorEstimator::with_config(wls_config) }
     }
 
@@ -55,8 +98,32 @@ impl FactorReturnsEstimator {
         &self.config
     }
 
+    /// Validate input frames before estimation, reporting every violation.
+    ///
+    /// See [`validate_inputs`](crate::validate_inputs) for the checks.
+    /// [`ReturnsEstimator::estimate`] runs the same checks first and fails
+    /// with `EstimatorError::InvalidInput`; call this directly to get the
+    /// typed report.
+    ///
+    /// # Errors
+    /// Returns `ModelError::InvalidInput` listing every violation, or
+    /// `ModelError::Polars` if a frame cannot be evaluated.
+    pub fn validate(
+        &self,
+        returns_df: &LazyFrame,
+        mkt_cap_df: &LazyFrame,
+        sector_df: &LazyFrame,
+        style_df: &LazyFrame,
+    ) -> Result<(), ModelError> {
+        crate::validate_inputs(returns_df, mkt_cap_df, sector_df, style_df)?.into_result()
+    }
+
     /// Estimate factor returns for a single time period.
     ///
+    /// Styles are orthogonalized to the sectors when `residualize_styles` is
+    /// set; style-to-style rules need column names and apply only in
+    /// [`ReturnsEstimator::estimate`].
+    ///
     /// # Arguments
     /// * `returns` - Asset returns (n_assets,)
     /// * `mkt_caps` - Market capitalizations (n_assets,)
@@ -75,26 +142,104 @@ impl FactorReturnsEstimator {
         sector_scores: &Array2<f64>,
         style_scores: &Array2<f64>,
     ) -> Result<(Array1<f64>, Array1<f64>), ModelError> {
-        self.wls
-            .estimate_single(returns, mkt_caps, sector_scores, style_scores)
-            .map_err(ModelError::from)
+        let no_countries = Array2::zeros((returns.len(), 0));
+        self.estimate_single_with_countries(
+            returns,
+            mkt_caps,
+            sector_scores,
+            &no_countries,
+            style_scores,
+        )
     }
-}
 
-impl Default for FactorReturnsEstimator {
-    fn default() -> Self {
-        Self::new()
+    /// Estimate factor returns for a single period with a country factor block.
+    ///
+    /// Styles are orthogonalized as in [`FactorReturnsEstimator::estimate_single`].
+    ///
+    /// # Arguments
+    /// * `returns` - Asset returns (n_assets,)
+    /// * `mkt_caps` - Market capitalizations (n_assets,)
+    /// * `sector_scores` - Sector exposure matrix (n_assets x n_sectors)
+    /// * `country_scores` - Country exposure matrix (n_assets x n_countries)
+    /// * `style_scores` - Style score matrix (n_assets x n_styles)
+    ///
+    /// # Returns
+    /// Tuple of (factor_returns ordered as [market, sectors, countries, styles], residuals)
+    ///
+    /// # Errors
+    /// Returns `ModelError::UnderpopulatedCountry` if a country has fewer names
+    /// than `min_country_names`, or `ModelError` if estimation fails.
+    pub fn estimate_single_with_countries(
+        &self,
+        returns: &Array1<f64>,
+        mkt_caps: &Array1<f64>,
+        sector_scores: &Array2<f64>,
+        country_scores: &Array2<f64>,
+        style_scores: &Array2<f64>,
+    ) -> Result<(Array1<f64>, Array1<f64>), ModelError> {
+        let required = self.config.min_country_names.max(1);
+        for (column, exposures) in country_scores.columns().into_iter().enumerate() {
+            let names = exposures.iter().filter(|&&x| x != 0.0).count();
+            if names < required {
+                return Err(ModelError::UnderpopulatedCountry { column, names, required });
+            }
+        }
+
+        let mut style_scores = style_scores.clone();
+        let rows: Vec<usize> = (0..returns.len()).collect();
+        orthogonalize_styles(
+            &mut style_scores,
+            sector_scores,
+            mkt_caps,
+            &rows,
+            &[],
+            self.config.residualize_styles,
+        )?;
+
+        self.wls
+            .estimate_single_with_countries(
+                returns,
+                mkt_caps,
+                sector_scores,
+                country_scores,
+                &style_scores,
+            )
+            .map_err(ModelError::from)
     }
-}
 
-impl ReturnsEstimator for FactorReturnsEstimator {
-    fn estimate(
+    /// Estimate factor returns along with the exposures each regression used.
+    ///
+    /// Same as [`ReturnsEstimator::estimate`], plus the exposures of every
+    /// coverage asset on every estimated date as they entered the
+    /// regression: styles after orthogonalization, and zero for sectors and
+    /// countries dropped on that date. Risk built from these exposures is
+    /// consistent with the factor returns.
+    ///
+    /// # Returns
+    /// Tuple of (factor_returns_df, residual_returns_df, exposures_df), where
+    /// exposures_df has | date | symbol | sector_* | country_* | *_score |.
+    ///
+    /// # Errors
+    /// Returns `EstimatorError` if the inputs are invalid or estimation fails.
+    pub fn estimate_with_exposures(
         &self,
         returns_df: LazyFrame,
         mkt_cap_df: LazyFrame,
         sector_df: LazyFrame,
         style_df: LazyFrame,
-    ) -> Result<(DataFrame, DataFrame), EstimatorError> {
+    ) -> Result<(DataFrame, DataFrame, DataFrame), EstimatorError> {
+        let report = crate::validate_inputs(&returns_df, &mkt_cap_df, &sector_df, &style_df)
+            .map_err(|e| match e {
+                ModelError::Polars(e) => EstimatorError::Polars(e),
+                e => EstimatorError::InvalidInput(e.to_string()),
+            })?;
+        if !report.is_valid() {
+            return Err(EstimatorError::InvalidInput(report.to_string()));
+        }
+
+        let [returns_df, mkt_cap_df, sector_df, style_df] =
+            self.config.frequency.resample(returns_df, mkt_cap_df, sector_df, style_df);
+
         // Join all data on date and symbol
         let joined = returns_df
             .join(
@@ -114,8 +259,8 @@ impl ReturnsEstimator for FactorReturnsEstimator {
                 [col("date"), col("symbol")],
                 [col("date"), col("symbol")],
                 JoinArgs::new(JoinType::Inner),
-            )
-            .collect()?;
+            );
+        let joined = self.config.estimation_universe.mark(joined, ESTU_COLUMN).collect()?;
 
         if joined.height() == 0 {
             return Err(EstimatorError::InsufficientData { required: 1, actual: 0 });
@@ -128,6 +273,9 @@ impl ReturnsEstimator for FactorReturnsEstimator {
         let sector_cols: Vec<String> =
             all_columns.iter().filter(|c| c.starts_with("sector_")).cloned().collect();
 
+        let country_cols: Vec<String> =
+            all_columns.iter().filter(|c| c.starts_with("country_")).cloned().collect();
+
         let style_cols: Vec<String> = all_columns
             .iter()
             .filter(|c| c.ends_with("_score") && *c != "asset_returns")
@@ -138,6 +286,14 @@ impl ReturnsEstimator for FactorReturnsEstimator {
             return Err(EstimatorError::MissingColumn("sector_* columns".to_string()));
         }
 
+        let rules: Vec<ResolvedRule> =
+            resolve_rules(&style_cols, &self.config.style_orthogonalization).map_err(
+                |e| match e {
+                    ModelError::InvalidConfig(msg) => EstimatorError::InvalidConfig(msg),
+                    other => EstimatorError::InvalidConfig(other.to_string()),
+                },
+            )?;
+
         // Build result vectors
         let mut factor_dates: Vec<Date> = Vec::new();
         let mut factor_names: Vec<String> = Vec::new();
@@ -147,6 +303,10 @@ impl ReturnsEstimator for FactorReturnsEstimator {
         let mut residual_symbols: Vec<String> = Vec::new();
         let mut residual_values: Vec<f64> = Vec::new();
 
+        let exposure_cols: Vec<&String> =
+            sector_cols.iter().chain(&country_cols).chain(&style_cols).collect();
+        let mut exposure_values: Vec<Vec<f64>> = vec![Vec::new(); exposure_cols.len()];
+
         // Group by date and process each group
         let grouped = joined.clone().lazy().group_by([col("date")]).agg([col("*")]).collect()?;
 
@@ -171,14 +331,21 @@ impl ReturnsEstimator for FactorReturnsEstimator {
                 .collect()?;
 
             let n = date_filter.height();
-            if n < sector_cols.len() + style_cols.len() + 2 {
-                continue;
-            }
 
             // Extract arrays
             let returns = extract_array(&date_filter, "asset_returns")?;
             let mkt_caps = extract_array(&date_filter, "market_cap")?;
 
+            // Rows in the estimation universe; the remaining rows are coverage only
+            let estu: Vec<usize> = date_filter
+                .column(ESTU_COLUMN)?
+                .bool()?
+                .iter()
+                .enumerate()
+                .filter_map(|(i, member)| member.unwrap_or(false).then_some(i))
+                .collect();
+            let n_estu = estu.len();
+
             // Build sector matrix
             let mut sector_matrix = Array2::zeros((n, sector_cols.len()));
             for (j, col_name) in sector_cols.iter().enumerate() {
@@ -188,6 +355,57 @@ impl ReturnsEstimator for FactorReturnsEstimator {
                 }
             }
 
+            // Sector memberships may be fractional but must sum to one per asset
+            let invalid = invalid_membership_rows(&sector_matrix, MEMBERSHIP_TOLERANCE);
+            if let Some(&first) = invalid.first() {
+                let symbol = date_filter.column("symbol")?.str()?.get(first).unwrap_or("");
+                return Err(EstimatorError::InvalidExposures(format!(
+                    "{} sector exposure rows on {date_val} are negative or do not sum to one \
+                     (first: {symbol}, sum {:.6})",
+                    invalid.len(),
+                    sector_matrix.row(first).sum()
+                )));
+            }
+
+            // Skip sectors with no estimation universe members on this date
+            // (e.g. rolled up in a hierarchy)
+            let active_sectors: Vec<usize> = (0..sector_cols.len())
+                .filter(|&j| estu.iter().any(|&i| sector_matrix[[i, j]] != 0.0))
+                .collect();
+            let sector_matrix = sector_matrix.select(Axis(1), &active_sectors);
+
+            // Build country matrix, dropping countries with too few names on this date
+            let mut country_matrix = Array2::zeros((n, country_cols.len()));
+            for (j, col_name) in country_cols.iter().enumerate() {
+                let col_data = extract_array(&date_filter, col_name)?;
+                for i in 0..n {
+                    country_matrix[[i, j]] = col_data[i];
+                }
+            }
+            if !country_cols.is_empty() {
+                let invalid = invalid_membership_rows(&country_matrix, MEMBERSHIP_TOLERANCE);
+                if let Some(&first) = invalid.first() {
+                    let symbol = date_filter.column("symbol")?.str()?.get(first).unwrap_or("");
+                    return Err(EstimatorError::InvalidExposures(format!(
+                        "{} country exposure rows on {date_val} are negative or do not sum to \
+                         one (first: {symbol})",
+                        invalid.len()
+                    )));
+                }
+            }
+            let min_country_names = self.config.min_country_names.max(1);
+            let active_countries: Vec<usize> = (0..country_cols.len())
+                .filter(|&j| {
+                    estu.iter().filter(|&&i| country_matrix[[i, j]] != 0.0).count()
+                        >= min_country_names
+                })
+                .collect();
+            let country_matrix = country_matrix.select(Axis(1), &active_countries);
+
+            if n_estu < active_sectors.len() + active_countries.len() + style_cols.len() + 2 {
+                continue;
+            }
+
             // Build style matrix
             let mut style_matrix = Array2::zeros((n, style_cols.len().max(1)));
             if !style_cols.is_empty() {
@@ -199,12 +417,33 @@ impl ReturnsEstimator for FactorReturnsEstimator {
                 }
             }
 
-            // Estimate
-            let (factor_rets, residuals) =
-                match self.estimate_single(&returns, &mkt_caps, &sector_matrix, &style_matrix) {
-                    Ok(result) => result,
-                    Err(_) => continue,
-                };
+            // Orthogonalize styles, fitting on the estimation universe
+            if !style_cols.is_empty()
+                && orthogonalize_styles(
+                    &mut style_matrix,
+                    &sector_matrix,
+                    &mkt_caps,
+                    &estu,
+                    &rules,
+                    self.config.residualize_styles,
+                )
+                .is_err()
+            {
+                continue;
+            }
+
+            // Estimate on the estimation universe; styles are already orthogonalized
+            // and thin countries dropped
+            let (factor_rets, estu_residuals) = match self.wls.estimate_single_with_countries(
+                &returns.select(Axis(0), &estu),
+                &mkt_caps.select(Axis(0), &estu),
+                &sector_matrix.select(Axis(0), &estu),
+                &country_matrix.select(Axis(0), &estu),
+                &style_matrix.select(Axis(0), &estu),
+            ) {
+                Ok(result) => result,
+                Err(_) => continue,
+            };
 
             // Store factor returns
             // Market
@@ -213,19 +452,49 @@ impl ReturnsEstimator for FactorReturnsEstimator {
             factor_values.push(factor_rets[0]);
 
             // Sectors
-            for (i, name) in sector_cols.iter().enumerate() {
+            for (i, &j) in active_sectors.iter().enumerate() {
                 factor_dates.push(date_val);
-                factor_names.push(name.clone());
+                factor_names.push(sector_cols[j].clone());
                 factor_values.push(factor_rets[1 + i]);
             }
 
+            // Countries
+            for (i, &j) in active_countries.iter().enumerate() {
+                factor_dates.push(date_val);
+                factor_names.push(country_cols[j].clone());
+                factor_values.push(factor_rets[1 + active_sectors.len() + i]);
+            }
+
             // Styles
+            let style_offset = 1 + active_sectors.len() + active_countries.len();
             for (i, name) in style_cols.iter().enumerate() {
                 factor_dates.push(date_val);
                 factor_names.push(name.clone());
-                factor_values.push(factor_rets[1 + sector_cols.len() + i]);
+                factor_values.push(factor_rets[style_offset + i]);
             }
 
+            // Residuals: estimation universe rows keep the regression residuals (on
+            // winsorized returns); the remaining coverage rows get r - X f on raw returns
+            let residuals = if n_estu == n {
+                estu_residuals
+            } else {
+                let exposures = ndarray::concatenate(
+                    Axis(1),
+                    &[
+                        Array2::ones((n, 1)).view(),
+                        sector_matrix.view(),
+                        country_matrix.view(),
+                        style_matrix.view(),
+                    ],
+                )
+                .map_err(|e| EstimatorError::LinearAlgebra(e.to_string()))?;
+                let mut residuals = &returns - &exposures.dot(&factor_rets);
+                for (&i, &resid) in estu.iter().zip(&estu_residuals) {
+                    residuals[i] = resid;
+                }
+                residuals
+            };
+
             // Store residuals
             let symbols = date_filter.column("symbol")?.str()?;
             for i in 0..n {
@@ -233,6 +502,24 @@ impl ReturnsEstimator for FactorReturnsEstimator {
                 residual_symbols.push(symbols.get(i).unwrap_or("").to_string());
                 residual_values.push(residuals[i]);
             }
+
+            // Store the exposures the regression used: dropped sectors and countries
+            // are zero and styles are orthogonalized
+            let active = |active: &[usize], matrix: &Array2<f64>, j: usize| {
+                active
+                    .iter()
+                    .position(|&a| a == j)
+                    .map_or_else(|| vec![0.0; n], |k| matrix.column(k).to_vec())
+            };
+            let columns = (0..sector_cols.len())
+                .map(|j| active(&active_sectors, &sector_matrix, j))
+                .chain(
+                    (0..country_cols.len()).map(|j| active(&active_countries, &country_matrix, j)),
+                )
+                .chain((0..style_cols.len()).map(|j| style_matrix.column(j).to_vec()));
+            for (values, column) in exposure_values.iter_mut().zip(columns) {
+                values.extend(column);
+            }
         }
 
         // Build output DataFrames
@@ -242,12 +529,40 @@ impl ReturnsEstimator for FactorReturnsEstimator {
             Column::new("factor_return".into(), factor_values),
         ])?;
 
+        let mut exposure_df = DataFrame::new(vec![
+            Column::new("date".into(), residual_dates.clone()),
+            Column::new("symbol".into(), residual_symbols.clone()),
+        ])?;
+        for (name, values) in exposure_cols.into_iter().zip(exposure_values) {
+            exposure_df.with_column(Column::new(name.into(), values))?;
+        }
+
         let residual_df = DataFrame::new(vec![
             Column::new("date".into(), residual_dates),
             Column::new("symbol".into(), residual_symbols),
             Column::new("residual_return".into(), residual_values),
         ])?;
 
+        Ok((factor_df, residual_df, exposure_df))
+    }
+}
+
+impl Default for FactorReturnsEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReturnsEstimator for FactorReturnsEstimator {
+    fn estimate(
+        &self,
+        returns_df: LazyFrame,
+        mkt_cap_df: LazyFrame,
+        sector_df: LazyFrame,
+        style_df: LazyFrame,
+    ) -> Result<(DataFrame, DataFrame), EstimatorError> {
+        let (factor_df, residual_df, _) =
+            self.estimate_with_exposures(returns_df, mkt_cap_df, sector_df, style_df)?;
         Ok((factor_df, residual_df))
     }
 
@@ -291,9 +606,341 @@ mod tests {
         assert!(estimator.residualize_styles());
     }
 
+    #[test]
+    fn estimate_single_matches_wls_estimator() {
+        let returns = ndarray::array![0.01, 0.02, 0.015, 0.025, 0.03, 0.01];
+        let caps = ndarray::array![100.0, 200.0, 150.0, 250.0, 300.0, 100.0];
+        let sectors =
+            ndarray::array![[1.0, 0.0], [1.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [0.0, 1.0]];
+        let styles = ndarray::array![[0.5], [0.3], [0.2], [-0.2], [-0.3], [-0.5]];
+
+        let (expected, _) =
+            WlsFactorEstimator::new().estimate_single(&returns, &caps, &sectors, &styles).unwrap();
+        let (actual, _) = FactorReturnsEstimator::new()
+            .estimate_single(&returns, &caps, &sectors, &styles)
+            .unwrap();
+
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn estimate_with_country_block() {
+        let n = 12;
+        let symbols: Vec<String> = (0..n).map(|i| format!("S{i}")).collect();
+        let mut dates = Vec::new();
+        let mut syms = Vec::new();
+        let mut returns = Vec::new();
+        for d in 0..2 {
+            for (i, sym) in symbols.iter().enumerate() {
+                dates.push(Date::from_ymd_opt(2024, 1, 2 + d).unwrap());
+                syms.push(sym.clone());
+                returns.push(0.001 * (i as f64) - 0.004 + 0.002 * d as f64);
+            }
+        }
+        let tech: Vec<f64> = (0..2 * n).map(|k| if k % 2 == 0 { 1.0 } else { 0.0 }).collect();
+        let us: Vec<f64> = (0..2 * n).map(|k| if k % 3 == 0 { 1.0 } else { 0.0 }).collect();
+        let style: Vec<f64> = (0..2 * n).map(|k| ((k % n) as f64 - 5.5) / 5.5).collect();
+
+        let returns_df = df! {
+            "date" => &dates, "symbol" => &syms, "asset_returns" => &returns,
+        }
+        .unwrap();
+        let mkt_cap_df = df! {
+            "date" => &dates, "symbol" => &syms, "market_cap" => vec![1e9; 2 * n],
+        }
+        .unwrap();
+        let sector_df = df! {
+            "date" => &dates,
+            "symbol" => &syms,
+            "sector_Tech" => &tech,
+            "sector_Other" => tech.iter().map(|x| 1.0 - x).collect::<Vec<_>>(),
+            "country_US" => &us,
+            "country_JP" => us.iter().map(|x| 1.0 - x).collect::<Vec<_>>(),
+        }
+        .unwrap();
+        let style_df = df! {
+            "date" => &dates, "symbol" => &syms, "mom_score" => &style,
+        }
+        .unwrap();
+
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig {
+            winsor_factor: None,
+            ..Default::default()
+        });
+        let (factor_returns, residuals) = estimator
+            .estimate(returns_df.lazy(), mkt_cap_df.lazy(), sector_df.lazy(), style_df.lazy())
+            .unwrap();
+
+        // market + 2 sectors + 2 countries + 1 style, on 2 dates
+        assert_eq!(factor_returns.height(), 12);
+        assert_eq!(residuals.height(), 2 * n);
+
+        let countries = factor_returns
+            .lazy()
+            .filter(col("factor").eq(lit("country_US")).or(col("factor").eq(lit("country_JP"))))
+            .group_by([col("date")])
+            .agg([col("factor_return").sum()])
+            .collect()
+            .unwrap();
+        for total in countries.column("factor_return").unwrap().f64().unwrap().into_no_null_iter() {
+            assert!(total.abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn weekly_frequency_estimates_once_per_week() {
+        let n = 12;
+        // Thursday and Friday of one ISO week, then Monday to Thursday of the next
+        let days = [4, 5, 8, 9, 10, 11];
+        let mut dates = Vec::new();
+        let mut syms = Vec::new();
+        let mut returns = Vec::new();
+        for (d, &day) in days.iter().enumerate() {
+            for i in 0..n {
+                dates.push(Date::from_ymd_opt(2024, 1, day).unwrap());
+                syms.push(format!("S{i}"));
+                returns.push(0.001 * ((i * 5 + d) % n) as f64 - 0.005);
+            }
+        }
+        let rows = dates.len();
+        let tech: Vec<f64> = (0..rows).map(|k| if k % 2 == 0 { 1.0 } else { 0.0 }).collect();
+        let style: Vec<f64> = (0..rows).map(|k| ((k % n) as f64 - 5.5) / 5.5).collect();
+
+        let returns_df = df! {
+            "date" => &dates, "symbol" => &syms, "asset_returns" => &returns,
+        }
+        .unwrap();
+        let mkt_cap_df = df! {
+            "date" => &dates, "symbol" => &syms, "market_cap" => vec![1e9; rows],
+        }
+        .unwrap();
+        let sector_df = df! {
+            "date" => &dates,
+            "symbol" => &syms,
+            "sector_Tech" => &tech,
+            "sector_Other" => tech.iter().map(|x| 1.0 - x).collect::<Vec<_>>(),
+        }
+        .unwrap();
+        let style_df = df! {
+            "date" => &dates, "symbol" => &syms, "mom_score" => &style,
+        }
+        .unwrap();
+
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig {
+            winsor_factor: None,
+            frequency: EstimationFrequency::Weekly,
+            ..Default::default()
+        });
+        let (factor_returns, residuals) = estimator
+            .estimate(returns_df.lazy(), mkt_cap_df.lazy(), sector_df.lazy(), style_df.lazy())
+            .unwrap();
+
+        // One regression per week, labeled with the last trading day of the week
+        let weeks = factor_returns
+            .column("date")
+            .unwrap()
+            .unique()
+            .unwrap()
+            .sort(Default::default())
+            .unwrap();
+        let expected = [5, 11].map(|d| Date::from_ymd_opt(2024, 1, d).unwrap());
+        assert!(weeks.equals(&Column::new("date".into(), expected)));
+        assert_eq!(residuals.height(), 2 * n);
+    }
+
+    #[test]
+    fn estimation_universe_covers_all_assets() {
+        check_estimation_universe(None);
+    }
+
+    #[test]
+    fn estimation_universe_residuals_with_default_winsorization() {
+        check_estimation_universe(EstimatorConfig::default().winsor_factor);
+    }
+
+    /// Fit on the 20 largest of 30 names and compare with a model fit on those 20 alone.
+    fn check_estimation_universe(winsor_factor: Option<f64>) {
+        let n = 30;
+        let date = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let dates = vec![date; n];
+        let syms: Vec<String> = (0..n).map(|i| format!("S{i}")).collect();
+        let returns: Vec<f64> = (0..n).map(|i| 0.002 * ((i * 7 % n) as f64) - 0.01).collect();
+        let caps: Vec<f64> = (0..n).map(|i| 1e9 * (1.0 + i as f64)).collect();
+        let tech: Vec<f64> = (0..n).map(|i| if i % 2 == 0 { 1.0 } else { 0.0 }).collect();
+        let style: Vec<f64> = (0..n).map(|i| ((i * 5 % n) as f64 - 14.5) / 14.5).collect();
+
+        let inputs = |rows: &[usize]| {
+            let pick = |v: &[f64]| rows.iter().map(|&i| v[i]).collect::<Vec<_>>();
+            let d: Vec<Date> = rows.iter().map(|&i| dates[i]).collect();
+            let s: Vec<String> = rows.iter().map(|&i| syms[i].clone()).collect();
+            let other: Vec<f64> = pick(&tech).iter().map(|x| 1.0 - x).collect();
+            (
+                df! { "date" => &d, "symbol" => &s, "asset_returns" => pick(&returns) }
+                    .unwrap()
+                    .lazy(),
+                df! { "date" => &d, "symbol" => &s, "market_cap" => pick(&caps) }.unwrap().lazy(),
+                df! {
+                    "date" => &d, "symbol" => &s,
+                    "sector_Tech" => pick(&tech), "sector_Other" => other,
+                }
+                .unwrap()
+                .lazy(),
+                df! { "date" => &d, "symbol" => &s, "mom_score" => pick(&style) }.unwrap().lazy(),
+            )
+        };
+
+        let all: Vec<usize> = (0..n).collect();
+        let top: Vec<usize> = (n - 20..n).collect();
+
+        let (r, m, sec, sty) = inputs(&all);
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig {
+            winsor_factor,
+            estimation_universe: EstimationUniverse::TopN {
+                n: 20,
+                column: "market_cap".to_string(),
+            },
+            ..Default::default()
+        });
+        let (factors, residuals) = estimator.estimate(r, m, sec, sty).unwrap();
+
+        let (r, m, sec, sty) = inputs(&top);
+        let subset = FactorReturnsEstimator::with_config(EstimatorConfig {
+            winsor_factor,
+            ..Default::default()
+        });
+        let (expected, expected_residuals) = subset.estimate(r, m, sec, sty).unwrap();
+
+        // Factor returns are fit on the top 20 names only
+        let sorted = |df: DataFrame| {
+            df.lazy().sort(["factor"], SortMultipleOptions::default()).collect().unwrap()
+        };
+        let (factors, expected) = (sorted(factors), sorted(expected));
+        for (a, b) in factors
+            .column("factor_return")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .zip(expected.column("factor_return").unwrap().f64().unwrap().into_no_null_iter())
+        {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        // Residuals are reported for the whole coverage universe and agree on the subset
+        assert_eq!(residuals.height(), n);
+        let lookup = |df: &DataFrame, symbol: &str| {
+            let symbols = df.column("symbol").unwrap().str().unwrap();
+            let values = df.column("residual_return").unwrap().f64().unwrap();
+            let i = symbols.iter().position(|s| s == Some(symbol)).unwrap();
+            values.get(i).unwrap()
+        };
+        for &i in &top {
+            let a = lookup(&residuals, &syms[i]);
+            let b = lookup(&expected_residuals, &syms[i]);
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn orthogonalization_rule_must_name_styles() {
+        let date = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let frame = |name: &str, values: Vec<f64>| {
+            df! { "date" => vec![date; 4], "symbol" => &["A", "B", "C", "D"], name => values }
+                .unwrap()
+                .lazy()
+        };
+
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig {
+            style_orthogonalization: vec![StyleOrthogonalization::new(
+                "resvol_score",
+                ["beta_score"],
+            )],
+            ..Default::default()
+        });
+        let result = estimator.estimate(
+            frame("asset_returns", vec![0.01, 0.02, -0.01, 0.0]),
+            frame("market_cap", vec![1e9; 4]),
+            frame("sector_Tech", vec![1.0; 4]),
+            frame("mom_score", vec![1.0, -1.0, 0.5, -0.5]),
+        );
+        assert!(matches!(result, Err(EstimatorError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn exposures_are_the_regression_inputs() {
+        let date = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let symbols: Vec<String> = (0..8).map(|i| format!("S{i}")).collect();
+        let frame = |columns: Vec<(&str, Vec<f64>)>| {
+            let mut df = df! { "date" => vec![date; 8], "symbol" => &symbols }.unwrap();
+            for (name, values) in columns {
+                df.with_column(Column::new(name.into(), values)).unwrap();
+            }
+            df.lazy()
+        };
+        let tech = vec![1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+        let other: Vec<f64> = tech.iter().map(|t| 1.0 - t).collect();
+        let caps = vec![1.0, 2.0, 3.0, 4.0, 4.0, 3.0, 2.0, 1.0];
+
+        let (factor_returns, _, exposures) = FactorReturnsEstimator::new()
+            .estimate_with_exposures(
+                frame(vec![(
+                    "asset_returns",
+                    vec![0.01, 0.02, -0.01, 0.0, 0.03, -0.02, 0.01, 0.0],
+                )]),
+                frame(vec![("market_cap", caps.clone())]),
+                frame(vec![
+                    ("sector_Tech", tech.clone()),
+                    ("sector_Other", other),
+                    ("sector_Empty", vec![0.0; 8]),
+                ]),
+                frame(vec![("mom_score", vec![1.0, 0.5, 0.2, -0.1, 0.8, -0.6, -1.2, 0.4])]),
+            )
+            .unwrap();
+
+        // The empty sector is dropped from the regression and has zero exposure
+        assert_eq!(factor_returns.height(), 4);
+        let column = |name: &str| -> Vec<f64> {
+            exposures.column(name).unwrap().f64().unwrap().into_no_null_iter().collect()
+        };
+        assert_eq!(column("sector_Empty"), vec![0.0; 8]);
+        assert_eq!(column("sector_Tech"), tech);
+
+        // Styles are the sector-orthogonalized scores the regression used
+        let mom = column("mom_score");
+        let tech_cov: f64 = (0..8).map(|i| caps[i] * tech[i] * mom[i]).sum();
+        assert!(tech_cov.abs() < 1e-12);
+    }
+
+    #[test]
+    fn estimate_validates_inputs() {
+        let date = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let frame = |name: &str, values: Vec<f64>| {
+            df! { "date" => vec![date; 4], "symbol" => &["A", "B", "C", "D"], name => values }
+                .unwrap()
+                .lazy()
+        };
+
+        let result = FactorReturnsEstimator::new().estimate(
+            frame("asset_returns", vec![0.01, 0.02, -0.01, 0.0]),
+            frame("market_cap", vec![1e9, -1.0, 1e9, 1e9]),
+            frame("sector_Tech", vec![1.0, 1.0, 0.5, 1.0]),
+            frame("mom_score", vec![1.0, -1.0, 0.5, -0.5]),
+        );
+        let Err(EstimatorError::InvalidInput(message)) = result else {
+            panic!("expected invalid inputs to be rejected");
+        };
+        assert!(message.contains("2 violation(s)"));
+    }
+
     #[test]
     fn estimator_custom_config() {
-        let config = EstimatorConfig { winsor_factor: Some(0.10), residualize_styles: false };
+        let config = EstimatorConfig {
+            winsor_factor: Some(0.10),
+            residualize_styles: false,
+            ..Default::default()
+        };
         let estimator = FactorReturnsEstimator::with_config(config);
         assert_eq!(estimator.winsor_factor(), Some(0.10));
         assert!(!estimator.residualize_styles());
@@ -0,0 +1,175 @@
+//! Estimation frequency and input resampling.
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use toraniko_utils::{ReturnFrequency, ReturnKind, compound_returns};
+
+/// Frequency of the factor model.
+///
+/// Lower-frequency models compound daily returns over each period and use
+/// exposures sampled at the start of the period, which reduces the effect of
+/// asynchronous prices and short-term noise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EstimationFrequency {
+    /// One regression per trading day.
+    #[default]
+    Daily,
+    /// One regression per ISO calendar week.
+    Weekly,
+    /// One regression per calendar month.
+    Monthly,
+}
+
+impl EstimationFrequency {
+    /// Number of periods in a year (252 trading days, 52 weeks or 12 months).
+    #[must_use]
+    pub const fn periods_per_year(self) -> f64 {
+        match self {
+            Self::Daily => 252.0,
+            Self::Weekly => 52.0,
+            Self::Monthly => 12.0,
+        }
+    }
+
+    /// Factor that converts a variance per period of this frequency into a
+    /// variance per period of `target`, assuming uncorrelated returns.
+    #[must_use]
+    pub fn variance_scale(self, target: Self) -> f64 {
+        self.periods_per_year() / target.periods_per_year()
+    }
+
+    /// Calendar frequency used to compound returns (None for daily).
+    const fn return_frequency(self) -> Option<ReturnFrequency> {
+        match self {
+            Self::Daily => None,
+            Self::Weekly => Some(ReturnFrequency::Weekly),
+            Self::Monthly => Some(ReturnFrequency::Monthly),
+        }
+    }
+
+    /// Expressions identifying the period of the `date` column.
+    fn period_keys(frequency: ReturnFrequency) -> [Expr; 2] {
+        let date = || col("date").dt();
+        let [outer, inner] = match frequency {
+            ReturnFrequency::Weekly => [date().iso_year(), date().week()],
+            ReturnFrequency::Monthly => [date().year(), date().month()],
+        };
+        [outer.alias("_period_outer"), inner.alias("_period_inner")]
+    }
+
+    /// Resample daily estimator inputs to this frequency.
+    ///
+    /// Asset returns are compounded over each period with
+    /// [`compound_returns`]. Market caps, sector and style exposures are taken
+    /// from each symbol's first observation in the period, so the regression
+    /// only uses information available when the period began. Every frame is
+    /// labeled with the last trading date of the period in `returns`.
+    pub(crate) fn resample(
+        self,
+        returns: LazyFrame,
+        mkt_caps: LazyFrame,
+        sectors: LazyFrame,
+        styles: LazyFrame,
+    ) -> [LazyFrame; 4] {
+        let Some(frequency) = self.return_frequency() else {
+            return [returns, mkt_caps, sectors, styles];
+        };
+        let keys = Self::period_keys(frequency);
+        let key_cols = [col("_period_outer"), col("_period_inner")];
+
+        let period_ends = returns
+            .clone()
+            .with_columns(keys.clone())
+            .group_by(key_cols.clone())
+            .agg([col("date").max().alias("_period_end")]);
+
+        let relabel = |lf: LazyFrame| {
+            lf.join(
+                period_ends.clone(),
+                key_cols.clone(),
+                key_cols.clone(),
+                JoinArgs::new(JoinType::Inner),
+            )
+            .with_column(col("_period_end").alias("date"))
+            .drop(["_period_outer", "_period_inner", "_period_end"])
+        };
+
+        let compounded = relabel(
+            compound_returns(returns, "asset_returns", frequency, ReturnKind::Simple)
+                .with_columns(keys.clone()),
+        )
+        .select([col("date"), col("symbol"), col("asset_returns")]);
+
+        let period_start = |lf: LazyFrame| {
+            let first =
+                col("date").min().over([col("symbol"), key_cols[0].clone(), key_cols[1].clone()]);
+            relabel(lf.with_columns(keys.clone()).filter(col("date").eq(first)))
+        };
+
+        [compounded, period_start(mkt_caps), period_start(sectors), period_start(styles)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use toraniko_primitives::Date;
+
+    use super::*;
+
+    #[test]
+    fn monthly_resampling() {
+        let dates: Vec<Date> = [(1, 30), (1, 31), (2, 1), (2, 2)]
+            .iter()
+            .map(|&(m, d)| Date::from_ymd_opt(2024, m, d).unwrap())
+            .collect();
+        let returns = df! {
+            "date" => &dates,
+            "symbol" => &["A", "A", "A", "A"],
+            "asset_returns" => &[0.1, -0.1, 0.02, 0.03],
+        }
+        .unwrap()
+        .lazy();
+        let exposures = df! {
+            "date" => &dates[1..],
+            "symbol" => &["A", "A", "A"],
+            "mom_score" => &[1.0, 2.0, 3.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let [returns, _, _, styles] = EstimationFrequency::Monthly.resample(
+            returns,
+            exposures.clone(),
+            exposures.clone(),
+            exposures,
+        );
+        let sort = SortMultipleOptions::default();
+        let returns = returns.sort(["date"], sort.clone()).collect().unwrap();
+        let styles = styles.sort(["date"], sort).collect().unwrap();
+
+        let r: Vec<f64> =
+            returns.column("asset_returns").unwrap().f64().unwrap().into_no_null_iter().collect();
+        assert_relative_eq!(r[0], 1.1 * 0.9 - 1.0, epsilon = 1e-12);
+        assert_relative_eq!(r[1], 1.02 * 1.03 - 1.0, epsilon = 1e-12);
+
+        // Exposures from the first observation of each month, labeled with the period end
+        let scores: Vec<f64> =
+            styles.column("mom_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+        assert_eq!(scores, vec![1.0, 2.0]);
+        assert!(styles.column("date").unwrap().equals(returns.column("date").unwrap()));
+        assert!(styles.column("_period_end").is_err());
+    }
+
+    #[test]
+    fn variance_scaling() {
+        assert_relative_eq!(
+            EstimationFrequency::Daily.variance_scale(EstimationFrequency::Monthly),
+            21.0
+        );
+        assert_relative_eq!(
+            EstimationFrequency::Weekly.variance_scale(EstimationFrequency::Weekly),
+            1.0
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Hierarchical industry classification exposures.
+//!
+//! Builds `sector_*` exposure columns from a sector → industry group → industry
+//! classification, estimating factors at a chosen level and rolling thinly
+//! populated nodes up to coarser levels on a per-date basis.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use polars::prelude::*;
+use toraniko_primitives::{IndustryHierarchy, IndustryLevel};
+
+use crate::ModelError;
+
+/// Configuration for hierarchical sector exposures.
+#[derive(Debug, Clone)]
+pub struct HierarchyConfig {
+    /// Level at which factors are estimated.
+    pub level: IndustryLevel,
+    /// Minimum number of names a node needs on a date before it is rolled up.
+    pub min_names: usize,
+}
+
+impl Default for HierarchyConfig {
+    fn default() -> Self {
+        Self { level: IndustryLevel::Industry, min_names: 5 }
+    }
+}
+
+/// Build one-hot sector exposures from a hierarchical classification.
+///
+/// Each asset is mapped to its ancestor at `config.level`. On every date, nodes
+/// with fewer than `config.min_names` assets are rolled up to their parent (see
+/// [`IndustryHierarchy::roll_up`]), so the set of estimated factors may differ
+/// between dates. Nodes that are unused on a date have all-zero exposures and
+/// are skipped by the estimator for that date.
+///
+/// Columns are named `sector_{level}_{name}` (e.g. `sector_industry_group_Energy`),
+/// since nodes at different levels may share a name.
+///
+/// # Arguments
+/// * `df` - LazyFrame with | date | symbol | `industry_col` |, where `industry_col`
+///   holds the finest-level (industry) classification
+/// * `hierarchy` - Classification hierarchy
+/// * `industry_col` - Name of the industry column
+/// * `config` - Estimation level and roll-up threshold
+///
+/// # Returns
+/// DataFrame with | date | symbol | sector_* | columns, one per `(level, name)` node
+/// used on any date.
+///
+/// # Errors
+/// Returns `ModelError` if columns are missing, keys are null, or an industry
+/// is not part of the hierarchy.
+pub fn hierarchical_sector_exposures(
+    df: LazyFrame,
+    hierarchy: &IndustryHierarchy,
+    industry_col: &str,
+    config: &HierarchyConfig,
+) -> Result<DataFrame, ModelError> {
+    let df = df.select([col("date"), col("symbol"), col(industry_col)]).collect()?;
+
+    let industries = df.column(industry_col)?.str()?;
+    let date_keys = df.column("date")?.cast(&DataType::Int32)?;
+    let date_keys = date_keys.i32()?;
+
+    // Map every row to its node at the configured level
+    let mut rows: Vec<(i32, &str)> = Vec::with_capacity(df.height());
+    for (date, industry) in date_keys.iter().zip(industries.iter()) {
+        let date = date.ok_or_else(|| ModelError::InvalidConfig("null date".to_string()))?;
+        let industry = industry
+            .ok_or_else(|| ModelError::InvalidConfig(format!("null value in {industry_col}")))?;
+        let node = hierarchy.ancestor(industry, config.level).ok_or_else(|| {
+            ModelError::InvalidConfig(format!("industry {industry} is not in the hierarchy"))
+        })?;
+        rows.push((date, node));
+    }
+
+    // Count names per node on each date and resolve the roll-up
+    let mut counts: HashMap<i32, HashMap<String, usize>> = HashMap::new();
+    for &(date, node) in &rows {
+        *counts.entry(date).or_default().entry(node.to_string()).or_default() += 1;
+    }
+    let mappings: HashMap<i32, BTreeMap<String, (IndustryLevel, String)>> = counts
+        .iter()
+        .map(|(&date, c)| (date, hierarchy.roll_up(config.level, c, config.min_names)))
+        .collect();
+
+    let effective: Vec<&(IndustryLevel, String)> =
+        rows.iter().map(|(date, node)| &mappings[date][*node]).collect();
+    let nodes: BTreeSet<&(IndustryLevel, String)> = effective.iter().copied().collect();
+
+    let mut columns = vec![df.column("date")?.clone(), df.column("symbol")?.clone()];
+    for node in nodes {
+        let values: Vec<f64> =
+            effective.iter().map(|&e| if e == node { 1.0 } else { 0.0 }).collect();
+        let (level, name) = node;
+        columns.push(Column::new(format!("sector_{level}_{name}").into(), values));
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hierarchy() -> IndustryHierarchy {
+        IndustryHierarchy::from_triples([
+            ("Tech", "Software", "Apps"),
+            ("Tech", "Software", "Systems"),
+            ("Tech", "Hardware", "Chips"),
+            ("Energy", "Oil", "Drilling"),
+        ])
+    }
+
+    #[test]
+    fn exposures_roll_up_per_date() {
+        // Date 1: Systems has a single name and rolls up; date 2: it is well populated.
+        let df = df! {
+            "date" => &[1, 1, 1, 1, 1, 2, 2, 2, 2, 2],
+            "symbol" => &["A", "B", "C", "D", "E", "A", "B", "C", "D", "E"],
+            "industry" => &[
+                "Apps", "Apps", "Systems", "Drilling", "Drilling",
+                "Apps", "Apps", "Systems", "Systems", "Drilling",
+            ],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = HierarchyConfig { level: IndustryLevel::Industry, min_names: 2 };
+        let result = hierarchical_sector_exposures(df, &hierarchy(), "industry", &config).unwrap();
+
+        for name in [
+            "sector_industry_Apps",
+            "sector_industry_Systems",
+            "sector_sector_Tech",
+            "sector_industry_Drilling",
+        ] {
+            assert!(result.column(name).is_ok(), "missing {name}");
+        }
+
+        let tech: Vec<f64> = result
+            .column("sector_sector_Tech")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(tech, vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+        // Every row has exactly one unit exposure
+        let sector_cols: Vec<String> = result
+            .get_column_names()
+            .iter()
+            .filter(|c| c.starts_with("sector_"))
+            .map(|c| c.to_string())
+            .collect();
+        for i in 0..result.height() {
+            let total: f64 = sector_cols
+                .iter()
+                .map(|c| result.column(c).unwrap().f64().unwrap().get(i).unwrap())
+                .sum();
+            assert_eq!(total, 1.0);
+        }
+    }
+
+    #[test]
+    fn exposures_at_sector_level() {
+        let df = df! {
+            "date" => &[1, 1, 1],
+            "symbol" => &["A", "B", "C"],
+            "industry" => &["Apps", "Chips", "Drilling"],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = HierarchyConfig { level: IndustryLevel::Sector, min_names: 1 };
+        let result = hierarchical_sector_exposures(df, &hierarchy(), "industry", &config).unwrap();
+
+        assert_eq!(result.width(), 4);
+        assert!(result.column("sector_sector_Tech").is_ok());
+        assert!(result.column("sector_sector_Energy").is_ok());
+    }
+
+    #[test]
+    fn nodes_sharing_a_name_across_levels_stay_separate() {
+        // "Energy" is both a sector and one of its industry groups.
+        let hierarchy = IndustryHierarchy::from_triples([
+            ("Energy", "Energy", "Drilling"),
+            ("Energy", "Energy", "Refining"),
+            ("Energy", "Services", "Equipment"),
+        ]);
+        let df = df! {
+            "date" => &[1, 1, 1],
+            "symbol" => &["A", "B", "C"],
+            "industry" => &["Drilling", "Refining", "Equipment"],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = HierarchyConfig { level: IndustryLevel::IndustryGroup, min_names: 2 };
+        let result = hierarchical_sector_exposures(df, &hierarchy, "industry", &config).unwrap();
+
+        assert_eq!(result.width(), 4);
+        let group: Vec<f64> = result
+            .column("sector_industry_group_Energy")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        let sector: Vec<f64> = result
+            .column("sector_sector_Energy")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(group, vec![1.0, 1.0, 0.0]);
+        assert_eq!(sector, vec![0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn unknown_industry_errors() {
+        let df = df! {
+            "date" => &[1],
+            "symbol" => &["A"],
+            "industry" => &["Banks"],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = hierarchical_sector_exposures(
+            df,
+            &hierarchy(),
+            "industry",
+            &HierarchyConfig::default(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Incremental daily updates.
+
+use polars::prelude::*;
+use toraniko_traits::ReturnsEstimator;
+
+use crate::{EstimationFrequency, FactorReturnsEstimator, ModelError, RiskState};
+
+/// Estimate only the dates after the last one in `state` and fold them in.
+///
+/// Factor returns are estimated independently per date, so estimating the
+/// new dates and appending them to previously persisted outputs gives the
+/// same factor returns and residuals as a full rerun, and the updated state
+/// gives the same covariance and specific variances. The inputs may cover the
+/// full history; older dates are filtered out before estimation.
+///
+/// # Arguments
+/// * `estimator` - Daily factor returns estimator
+/// * `state` - Risk state after the previous run, updated in place
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+/// * `sector_df` - LazyFrame with | date | symbol | sector_* |
+/// * `style_df` - LazyFrame with | date | symbol | *_score |
+///
+/// # Returns
+/// Tuple of (factor_returns, residuals) for the new dates only, empty if
+/// there are none.
+///
+/// # Errors
+/// Returns `ModelError::InvalidConfig` for a weekly or monthly estimator
+/// (whose last period may still be incomplete), `ModelError::Estimator` with
+/// `EstimatorError::InvalidInput` if the new rows fail validation, or an
+/// error if estimation fails.
+/// `state` is left unchanged on error.
+pub fn incremental_update(
+    estimator: &FactorReturnsEstimator,
+    state: &mut RiskState,
+    returns_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    sector_df: LazyFrame,
+    style_df: LazyFrame,
+) -> Result<(DataFrame, DataFrame), ModelError> {
+    if estimator.config().frequency != EstimationFrequency::Daily {
+        return Err(ModelError::InvalidConfig(
+            "incremental updates require a daily estimator".to_string(),
+        ));
+    }
+
+    let new_dates = |lf: LazyFrame| match state.last_date() {
+        Some(last) => lf.filter(col("date").gt(lit(last))),
+        None => lf,
+    };
+    let returns_df = new_dates(returns_df);
+
+    let pending = returns_df.clone().select([len()]).collect()?;
+    if pending.column("len")?.u32()?.get(0).unwrap_or(0) == 0 {
+        return Ok(empty_outputs()?);
+    }
+
+    let [mkt_cap_df, sector_df, style_df] = [mkt_cap_df, sector_df, style_df].map(new_dates);
+
+    let (factor_returns, residuals) =
+        estimator.estimate(returns_df, mkt_cap_df, sector_df, style_df)?;
+    state.update(&factor_returns, &residuals)?;
+
+    Ok((factor_returns, residuals))
+}
+
+/// Empty factor returns and residuals frames with the estimator's schema.
+fn empty_outputs() -> PolarsResult<(DataFrame, DataFrame)> {
+    let date = || Series::new_empty("date".into(), &DataType::Date).into_column();
+    let factor_returns = DataFrame::new(vec![
+        date(),
+        Series::new_empty("factor".into(), &DataType::String).into_column(),
+        Series::new_empty("factor_return".into(), &DataType::Float64).into_column(),
+    ])?;
+    let residuals = DataFrame::new(vec![
+        date(),
+        Series::new_empty("symbol".into(), &DataType::String).into_column(),
+        Series::new_empty("residual_return".into(), &DataType::Float64).into_column(),
+    ])?;
+    Ok((factor_returns, residuals))
+}
+
+#[cfg(test)]
+mod tests {
+    use toraniko_primitives::Date;
+
+    use super::*;
+    use crate::{EstimatorConfig, RiskConfig, factor_covariance, specific_variance};
+
+    /// Synthetic inputs for 12 symbols over `days` consecutive dates.
+    fn inputs(days: u32) -> [LazyFrame; 4] {
+        let n = 12;
+        let mut dates = Vec::new();
+        let mut syms = Vec::new();
+        let mut returns = Vec::new();
+        for d in 0..days {
+            for i in 0..n {
+                dates.push(Date::from_ymd_opt(2024, 1, 1 + d).unwrap());
+                syms.push(format!("S{i}"));
+                returns.push(0.001 * ((i * 7 + d as usize * 3) % n) as f64 - 0.005);
+            }
+        }
+        let rows = dates.len();
+        let tech: Vec<f64> = (0..rows).map(|k| if k % 3 == 0 { 1.0 } else { 0.0 }).collect();
+        let style: Vec<f64> = (0..rows).map(|k| ((k * 5 % n) as f64 - 5.5) / 5.5).collect();
+        let caps: Vec<f64> = (0..rows).map(|k| 1e9 * (1 + k % n) as f64).collect();
+
+        [
+            df! { "date" => &dates, "symbol" => &syms, "asset_returns" => &returns },
+            df! { "date" => &dates, "symbol" => &syms, "market_cap" => &caps },
+            df! {
+                "date" => &dates,
+                "symbol" => &syms,
+                "sector_Tech" => &tech,
+                "sector_Other" => tech.iter().map(|x| 1.0 - x).collect::<Vec<_>>(),
+            },
+            df! { "date" => &dates, "symbol" => &syms, "mom_score" => &style },
+        ]
+        .map(|df| df.unwrap().lazy())
+    }
+
+    fn sorted(df: DataFrame, by: &str) -> DataFrame {
+        df.sort(["date", by], SortMultipleOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn incremental_matches_full_rerun() {
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig::default());
+        let risk = RiskConfig { factor_half_life: 3, specific_half_life: 2, ..Default::default() };
+
+        let [r, m, s, st] = inputs(10);
+        let (full_factors, full_residuals) = estimator.estimate(r, m, s, st).unwrap();
+
+        // Day one run over the first six dates, then an update with the full history
+        let mut state = RiskState::new(risk).unwrap();
+        let [r, m, s, st] = inputs(6);
+        let (first_factors, first_residuals) =
+            incremental_update(&estimator, &mut state, r, m, s, st).unwrap();
+        let [r, m, s, st] = inputs(10);
+        let (new_factors, new_residuals) =
+            incremental_update(&estimator, &mut state, r, m, s, st).unwrap();
+        assert_eq!(state.last_date(), Date::from_ymd_opt(2024, 1, 10));
+        assert!(first_factors.height() > 0 && new_factors.height() > 0);
+
+        let factors = first_factors.vstack(&new_factors).unwrap();
+        let residuals = first_residuals.vstack(&new_residuals).unwrap();
+        assert!(sorted(factors, "factor").equals(&sorted(full_factors.clone(), "factor")));
+        assert!(sorted(residuals, "symbol").equals(&sorted(full_residuals.clone(), "symbol")));
+
+        assert_eq!(state.covariance().unwrap(), factor_covariance(&full_factors, &risk).unwrap());
+        assert!(
+            state
+                .specific_variance()
+                .unwrap()
+                .equals(&specific_variance(&full_residuals, &risk).unwrap())
+        );
+
+        // Nothing new to process
+        let [r, m, s, st] = inputs(10);
+        let (none, _) = incremental_update(&estimator, &mut state, r, m, s, st).unwrap();
+        assert_eq!(none.height(), 0);
+    }
+
+    #[test]
+    fn rejects_lower_frequencies() {
+        let estimator = FactorReturnsEstimator::with_config(EstimatorConfig {
+            frequency: EstimationFrequency::Weekly,
+            ..Default::default()
+        });
+        let mut state = RiskState::new(RiskConfig::default()).unwrap();
+        let [r, m, s, st] = inputs(3);
+        let result = incremental_update(&estimator, &mut state, r, m, s, st);
+        assert!(matches!(result, Err(ModelError::InvalidConfig(_))));
+    }
+}
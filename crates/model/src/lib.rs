@@ -9,12 +9,42 @@ pub use attribution::{AttributionResult, FactorContribution, compute_attribution
 mod factor_returns;
 pub use factor_returns::{EstimatorConfig, FactorReturnsEstimator};
 
+mod frequency;
+pub use frequency::EstimationFrequency;
+
+mod quality;
+pub use quality::{DataQualityReport, QualityConfig, data_quality_report};
+
+mod risk;
+pub use risk::{FactorCovariance, RiskConfig, RiskState, factor_covariance, specific_variance};
+
+mod incremental;
+pub use incremental::incremental_update;
+
 mod wls;
 pub use wls::{WlsConfig, WlsFactorEstimator};
 
 mod constraints;
 pub use constraints::{ConstraintType, SectorConstraint};
 
+mod currency;
+pub use currency::to_base_currency;
+
+mod exposures;
+pub use exposures::{MEMBERSHIP_TOLERANCE, fractional_sector_exposures, invalid_membership_rows};
+
+mod hierarchy;
+pub use hierarchy::{HierarchyConfig, hierarchical_sector_exposures};
+
+mod orthogonalize;
+pub use orthogonalize::StyleOrthogonalization;
+
+mod universe;
+pub use universe::EstimationUniverse;
+
+mod validate;
+pub use validate::{InputFrame, SchemaViolation, ValidationReport, validate_inputs};
+
 mod error;
 pub use error::ModelError;
 
@@ -0,0 +1,158 @@
+//! Style factor orthogonalization.
+//!
+//! Styles that are mechanically related (residual volatility and beta,
+//! non-linear size and size) are orthogonalized to each other before they
+//! enter the factor regression, so each factor return captures only the
+//! incremental effect.
+
+use ndarray::{Array1, Array2, Axis, concatenate};
+use serde::{Deserialize, Serialize};
+use toraniko_math::weighted_least_squares;
+
+use crate::ModelError;
+
+/// Orthogonalize one style to a list of other styles on every date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleOrthogonalization {
+    /// Style column to orthogonalize (e.g. `resvol_score`).
+    pub style: String,
+    /// Style columns it is orthogonalized to (e.g. `beta_score`, `sze_score`).
+    pub against: Vec<String>,
+}
+
+impl StyleOrthogonalization {
+    /// Create a new orthogonalization rule.
+    #[must_use]
+    pub fn new<S: Into<String>>(style: S, against: impl IntoIterator<Item = S>) -> Self {
+        Self { style: style.into(), against: against.into_iter().map(Into::into).collect() }
+    }
+}
+
+/// A [`StyleOrthogonalization`] resolved to style column indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedRule {
+    target: usize,
+    against: Vec<usize>,
+}
+
+/// Resolve orthogonalization rules against the style column names.
+///
+/// # Errors
+/// Returns `ModelError::InvalidConfig` if a rule names an unknown style.
+pub(crate) fn resolve_rules(
+    names: &[String],
+    rules: &[StyleOrthogonalization],
+) -> Result<Vec<ResolvedRule>, ModelError> {
+    let index = |name: &str| {
+        names.iter().position(|n| n == name).ok_or_else(|| {
+            ModelError::InvalidConfig(format!(
+                "orthogonalization refers to unknown style column {name}"
+            ))
+        })
+    };
+
+    rules
+        .iter()
+        .map(|rule| {
+            Ok(ResolvedRule {
+                target: index(&rule.style)?,
+                against: rule.against.iter().map(|a| index(a)).collect::<Result<_, _>>()?,
+            })
+        })
+        .collect()
+}
+
+/// Orthogonalize style columns in place for a single date.
+///
+/// Rules are applied in order, each regressing the target
+/// style on its `against` styles (plus the sector exposures when `to_sectors`
+/// is set) and keeping the residual. When `to_sectors` is set, styles without
+/// a rule are orthogonalized to the sectors alone. Coefficients are fit by WLS
+/// on `fit_rows` with `weights` and applied to every row.
+///
+/// # Errors
+/// Returns `ModelError::Math` if a regression is singular.
+pub(crate) fn orthogonalize_styles(
+    styles: &mut Array2<f64>,
+    sectors: &Array2<f64>,
+    weights: &Array1<f64>,
+    fit_rows: &[usize],
+    rules: &[ResolvedRule],
+    to_sectors: bool,
+) -> Result<(), ModelError> {
+    let sqrt_weights = weights.select(Axis(0), fit_rows).mapv(|w| w.max(0.0).sqrt());
+    let residualize = |styles: &mut Array2<f64>, target: usize, against: &[usize]| {
+        let mut regressors = styles.select(Axis(1), against);
+        if to_sectors {
+            regressors = concatenate(Axis(1), &[regressors.view(), sectors.view()])
+                .map_err(|e| ModelError::DimensionMismatch(e.to_string()))?;
+        }
+        if regressors.ncols() == 0 {
+            return Ok(());
+        }
+
+        let y = styles.column(target).select(Axis(0), fit_rows);
+        let fit = weighted_least_squares(&y, &regressors.select(Axis(0), fit_rows), &sqrt_weights)?;
+        let fitted = regressors.dot(&fit.coefficients);
+        let mut column = styles.column_mut(target);
+        column -= &fitted;
+        Ok::<(), ModelError>(())
+    };
+
+    let mut done = vec![false; styles.ncols()];
+    for rule in rules {
+        residualize(styles, rule.target, &rule.against)?;
+        done[rule.target] = true;
+    }
+
+    if to_sectors {
+        for target in (0..styles.ncols()).filter(|&j| !done[j]) {
+            residualize(styles, target, &[])?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn rules_and_sectors() {
+        let names = vec!["sze_score".to_string(), "resvol_score".to_string()];
+        let mut styles =
+            array![[1.0, 2.1], [-0.5, -0.4], [0.3, 0.9], [-1.2, -2.0], [0.8, 0.5], [-0.4, 0.2]];
+        let sectors =
+            array![[1.0, 0.0], [1.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 1.0], [0.0, 1.0]];
+        let weights = array![4.0, 1.0, 2.0, 3.0, 1.0, 2.0];
+        let fit_rows: Vec<usize> = (0..6).collect();
+        let rules =
+            resolve_rules(&names, &[StyleOrthogonalization::new("resvol_score", ["sze_score"])])
+                .unwrap();
+
+        orthogonalize_styles(&mut styles, &sectors, &weights, &fit_rows, &rules, true).unwrap();
+
+        let inner = |a: usize, b: &Array1<f64>| -> f64 {
+            (0..6).map(|i| weights[i] * styles[[i, a]] * b[i]).sum()
+        };
+        // Residual vol is orthogonal to size and to both sectors; size to the sectors
+        assert_relative_eq!(inner(1, &styles.column(0).to_owned()), 0.0, epsilon = 1e-10);
+        for k in 0..2 {
+            let sector = sectors.column(k).to_owned();
+            assert_relative_eq!(inner(0, &sector), 0.0, epsilon = 1e-10);
+            assert_relative_eq!(inner(1, &sector), 0.0, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn unknown_style_errors() {
+        let names = vec!["sze_score".to_string()];
+        let rules = [StyleOrthogonalization::new("sze_score", ["beta_score"])];
+        let result = resolve_rules(&names, &rules);
+        assert!(matches!(result, Err(ModelError::InvalidConfig(_))));
+    }
+}
@@ -0,0 +1,411 @@
+//! Data quality profiling of the estimator inputs.
+//!
+//! Profiles the same panels [`FactorReturnsEstimator`](crate::FactorReturnsEstimator)
+//! joins, so a bad vendor delivery (a collapsed universe, an empty sector,
+//! a column of nulls, a burst of extreme returns) shows up before it reaches
+//! the factor returns.
+
+use polars::prelude::*;
+use toraniko_primitives::Date;
+
+use crate::ModelError;
+
+/// Configuration for [`data_quality_report`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityConfig {
+    /// Absolute return above which a return counts as extreme.
+    pub extreme_return: f64,
+    /// Number of largest names whose combined cap weight is reported.
+    pub top_n: u32,
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self { extreme_return: 0.5, top_n: 10 }
+    }
+}
+
+/// Structured data quality report for the estimator inputs.
+#[derive(Debug, Clone)]
+pub struct DataQualityReport {
+    /// Per date: | date | returns | market_caps | sectors | styles | universe |
+    /// entered | exited | extreme_returns |, where the first four count rows
+    /// in each frame, `universe` counts names present in all four, `entered`
+    /// and `exited` count universe names added and dropped since the previous
+    /// date (zero on the first date), and `extreme_returns` counts returns
+    /// beyond the configured threshold.
+    pub coverage: DataFrame,
+    /// Per column: | frame | column | rows | missing | missing_rate |, where
+    /// missing counts nulls and NaNs.
+    pub missing: DataFrame,
+    /// Per date and sector: | date | sector | population |, counting names
+    /// with a positive exposure, including sectors with none.
+    pub sector_population: DataFrame,
+    /// Per date: | date | herfindahl | effective_names | top_n_weight |, from
+    /// cap weights.
+    pub concentration: DataFrame,
+}
+
+impl DataQualityReport {
+    /// Dates whose universe is below `min_fraction` of the median universe.
+    ///
+    /// # Errors
+    /// Returns `ModelError::Polars` if the coverage frame is malformed.
+    pub fn low_coverage_dates(&self, min_fraction: f64) -> Result<Vec<Date>, ModelError> {
+        let low = self
+            .coverage
+            .clone()
+            .lazy()
+            .filter(
+                col("universe")
+                    .cast(DataType::Float64)
+                    .lt(col("universe").cast(DataType::Float64).median() * lit(min_fraction)),
+            )
+            .select([col("date")])
+            .collect()?;
+        Ok(low.column("date")?.date()?.as_date_iter().flatten().collect())
+    }
+
+    /// Sectors that have no names on at least one date.
+    ///
+    /// # Errors
+    /// Returns `ModelError::Polars` if the sector population frame is malformed.
+    pub fn empty_sectors(&self) -> Result<Vec<String>, ModelError> {
+        let empty = self
+            .sector_population
+            .clone()
+            .lazy()
+            .filter(col("population").eq(lit(0)))
+            .select([col("sector").unique_stable()])
+            .collect()?;
+        Ok(empty.column("sector")?.str()?.iter().flatten().map(str::to_string).collect())
+    }
+}
+
+/// Profile the estimator inputs.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+/// * `sector_df` - LazyFrame with | date | symbol | sector_* |
+/// * `style_df` - LazyFrame with | date | symbol | *_score |
+/// * `config` - Thresholds for extreme returns and concentration
+///
+/// # Returns
+/// A [`DataQualityReport`] with per-date coverage, per-column missing rates,
+/// per-date sector populations and cap concentration.
+///
+/// # Errors
+/// Returns `ModelError::Polars` if a frame is missing an expected column or
+/// cannot be evaluated.
+pub fn data_quality_report(
+    returns_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    sector_df: LazyFrame,
+    style_df: LazyFrame,
+    config: &QualityConfig,
+) -> Result<DataQualityReport, ModelError> {
+    let frames = [
+        ("returns", returns_df),
+        ("market_caps", mkt_cap_df.clone()),
+        ("sectors", sector_df.clone()),
+        ("styles", style_df),
+    ];
+
+    let coverage = coverage(&frames, config)?;
+    let missing = missing(&frames)?;
+    let sector_population = sector_population(sector_df)?;
+    let concentration = concentration(mkt_cap_df, config.top_n)?;
+
+    Ok(DataQualityReport { coverage, missing, sector_population, concentration })
+}
+
+fn coverage(frames: &[(&str, LazyFrame); 4], config: &QualityConfig) -> PolarsResult<DataFrame> {
+    let keys = || [col("date"), col("symbol")];
+    let universe = frames[1..]
+        .iter()
+        .fold(frames[0].1.clone().select(keys()), |acc, (_, lf)| {
+            acc.join(lf.clone().select(keys()), keys(), keys(), JoinArgs::new(JoinType::Inner))
+        })
+        .unique(None, UniqueKeepStrategy::First);
+
+    // Dense ranks number the universe dates 1, 2, ...; a gap in a symbol's
+    // ranks means it left and later re-entered.
+    let ranked = universe
+        .with_column(
+            col("date").rank(RankOptions::default(), None).cast(DataType::Int64).alias("_t"),
+        )
+        .sort(["symbol", "date"], SortMultipleOptions::default())
+        .with_columns([
+            col("_t").shift(lit(1)).over([col("symbol")]).alias("_prev"),
+            col("_t").shift(lit(-1)).over([col("symbol")]).alias("_next"),
+            col("_t").max().alias("_last"),
+        ]);
+    let turnover = ranked.clone().group_by([col("date")]).agg([
+        len().alias("universe"),
+        col("_t").first(),
+        (col("_prev").is_null().and(col("_t").neq(lit(1))))
+            .or(col("_prev").neq(col("_t") - lit(1)))
+            .sum()
+            .alias("entered"),
+    ]);
+    let exits = ranked
+        .filter(
+            (col("_next").is_null().and(col("_t").neq(col("_last"))))
+                .or(col("_next").neq(col("_t") + lit(1))),
+        )
+        .group_by([col("_t") + lit(1)])
+        .agg([len().alias("exited")]);
+    let turnover =
+        turnover.join(exits, [col("_t")], [col("_t")], JoinArgs::new(JoinType::Left)).select([
+            col("date"),
+            col("universe"),
+            col("entered").cast(DataType::UInt32),
+            col("exited").fill_null(lit(0)).cast(DataType::UInt32),
+        ]);
+
+    let threshold = lit(config.extreme_return);
+    let returns = col("asset_returns");
+    let mut counts = frames[0].1.clone().group_by([col("date")]).agg([
+        len().alias(frames[0].0),
+        returns
+            .clone()
+            .is_not_nan()
+            .and(returns.clone().gt(threshold.clone()).or(returns.lt(-threshold)))
+            .sum()
+            .alias("extreme_returns"),
+    ]);
+    for (name, lf) in &frames[1..] {
+        let rows = lf.clone().group_by([col("date")]).agg([len().alias(*name)]);
+        counts = counts.join(
+            rows,
+            [col("date")],
+            [col("date")],
+            JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+        );
+    }
+
+    counts
+        .join(turnover, [col("date")], [col("date")], JoinArgs::new(JoinType::Left))
+        .select([
+            col("date"),
+            col("returns").fill_null(lit(0)),
+            col("market_caps").fill_null(lit(0)),
+            col("sectors").fill_null(lit(0)),
+            col("styles").fill_null(lit(0)),
+            col("universe").fill_null(lit(0)),
+            col("entered").fill_null(lit(0)),
+            col("exited").fill_null(lit(0)),
+            col("extreme_returns").fill_null(lit(0)).cast(DataType::UInt32),
+        ])
+        .sort(["date"], SortMultipleOptions::default())
+        .collect()
+}
+
+fn missing(frames: &[(&str, LazyFrame); 4]) -> PolarsResult<DataFrame> {
+    let mut frame_names = Vec::new();
+    let mut columns = Vec::new();
+    let mut rows = Vec::new();
+    let mut missing = Vec::new();
+
+    for (name, lf) in frames {
+        let schema = lf.clone().collect_schema()?;
+        let mut exprs = vec![len().alias("_rows")];
+        exprs.extend(schema.iter().map(|(column, dtype)| {
+            let mut is_missing = col(column.clone()).is_null();
+            if dtype.is_float() {
+                is_missing = is_missing.or(col(column.clone()).is_nan());
+            }
+            is_missing.sum().cast(DataType::UInt32).alias(column.clone())
+        }));
+        let counts = lf.clone().select(exprs).collect()?;
+
+        let total = counts.column("_rows")?.u32()?.get(0).unwrap_or(0);
+        for column in schema.iter_names() {
+            frame_names.push(*name);
+            columns.push(column.to_string());
+            rows.push(total);
+            missing.push(counts.column(column)?.u32()?.get(0).unwrap_or(0));
+        }
+    }
+
+    df! {
+        "frame" => frame_names,
+        "column" => columns,
+        "rows" => &rows,
+        "missing" => &missing,
+    }?
+    .lazy()
+    .with_column(
+        (col("missing").cast(DataType::Float64) / col("rows").cast(DataType::Float64))
+            .fill_nan(lit(0.0))
+            .alias("missing_rate"),
+    )
+    .collect()
+}
+
+fn sector_population(sector_df: LazyFrame) -> PolarsResult<DataFrame> {
+    let schema = sector_df.clone().collect_schema()?;
+    let sectors: Vec<PlSmallStr> =
+        schema.iter_names().filter(|name| name.starts_with("sector_")).cloned().collect();
+
+    let counts = sector_df.group_by([col("date")]).agg(
+        sectors
+            .iter()
+            .map(|sector| {
+                col(sector.clone()).gt(lit(0.0)).sum().cast(DataType::UInt32).alias(sector.clone())
+            })
+            .collect::<Vec<_>>(),
+    );
+    let long: Vec<LazyFrame> = sectors
+        .iter()
+        .map(|sector| {
+            counts.clone().select([
+                col("date"),
+                lit(sector.as_str()).alias("sector"),
+                col(sector.clone()).fill_null(lit(0)).alias("population"),
+            ])
+        })
+        .collect();
+    if long.is_empty() {
+        return DataFrame::new(vec![
+            Series::new_empty("date".into(), &DataType::Date).into_column(),
+            Series::new_empty("sector".into(), &DataType::String).into_column(),
+            Series::new_empty("population".into(), &DataType::UInt32).into_column(),
+        ]);
+    }
+
+    concat(long, UnionArgs::default())?
+        .sort(["date", "sector"], SortMultipleOptions::default())
+        .collect()
+}
+
+fn concentration(mkt_cap_df: LazyFrame, top_n: u32) -> PolarsResult<DataFrame> {
+    let rank_options = RankOptions { method: RankMethod::Ordinal, descending: true };
+    mkt_cap_df
+        .filter(col("market_cap").gt(lit(0.0)))
+        .with_columns([
+            (col("market_cap") / col("market_cap").sum().over([col("date")])).alias("_weight"),
+            col("market_cap").rank(rank_options, None).over([col("date")]).alias("_rank"),
+        ])
+        .group_by([col("date")])
+        .agg([
+            col("_weight").pow(2).sum().alias("herfindahl"),
+            col("_weight").filter(col("_rank").lt_eq(lit(top_n))).sum().alias("top_n_weight"),
+        ])
+        .with_column((lit(1.0) / col("herfindahl")).alias("effective_names"))
+        .select([col("date"), col("herfindahl"), col("effective_names"), col("top_n_weight")])
+        .sort(["date"], SortMultipleOptions::default())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn day(d: u32) -> Date {
+        Date::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn profiles_panels() {
+        let dates = [day(2), day(2), day(2), day(3), day(3), day(3)];
+        let symbols = ["A", "B", "C", "A", "C", "D"];
+        let returns = df! {
+            "date" => dates,
+            "symbol" => symbols,
+            "asset_returns" => [Some(0.01), Some(0.9), None, Some(0.02), Some(-0.6), Some(f64::NAN)],
+        }
+        .unwrap()
+        .lazy();
+        let mkt_caps = df! {
+            "date" => dates,
+            "symbol" => symbols,
+            "market_cap" => [3.0, 1.0, 1.0, 2.0, 1.0, 1.0],
+        }
+        .unwrap()
+        .lazy();
+        let sectors = df! {
+            "date" => dates,
+            "symbol" => symbols,
+            "sector_Tech" => [1.0, 1.0, 0.0, 1.0, 0.0, 0.0],
+            "sector_Energy" => [0.0, 0.0, 1.0, 0.0, 1.0, 1.0],
+        }
+        .unwrap()
+        .lazy();
+        let styles = df! {
+            "date" => dates,
+            "symbol" => symbols,
+            "mom_score" => [0.1, -0.1, 0.0, 0.2, -0.2, 0.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = QualityConfig { top_n: 1, ..Default::default() };
+        let report = data_quality_report(returns, mkt_caps, sectors, styles, &config).unwrap();
+
+        let coverage = &report.coverage;
+        let counts = |name: &str| coverage.column(name).unwrap().u32().unwrap().to_vec();
+        assert_eq!(counts("returns"), [Some(3), Some(3)]);
+        assert_eq!(counts("universe"), [Some(3), Some(3)]);
+        assert_eq!(counts("entered"), [Some(0), Some(1)]);
+        assert_eq!(counts("exited"), [Some(0), Some(1)]);
+        assert_eq!(counts("extreme_returns"), [Some(1), Some(1)]);
+
+        let missing = report
+            .missing
+            .clone()
+            .lazy()
+            .filter(col("column").eq(lit("asset_returns")))
+            .collect()
+            .unwrap();
+        assert_eq!(missing.column("missing").unwrap().u32().unwrap().get(0), Some(2));
+        let rate = missing.column("missing_rate").unwrap().f64().unwrap().get(0).unwrap();
+        assert_relative_eq!(rate, 2.0 / 6.0);
+
+        let population = report.sector_population.column("population").unwrap().u32().unwrap();
+        // Sorted by date, then sector name: Energy before Tech
+        assert_eq!(population.to_vec(), [Some(1), Some(2), Some(2), Some(1)]);
+        assert!(report.empty_sectors().unwrap().is_empty());
+
+        let concentration = &report.concentration;
+        let herfindahl = concentration.column("herfindahl").unwrap().f64().unwrap();
+        assert_relative_eq!(herfindahl.get(0).unwrap(), (9.0 + 1.0 + 1.0) / 25.0);
+        let top = concentration.column("top_n_weight").unwrap().f64().unwrap();
+        assert_relative_eq!(top.get(0).unwrap(), 0.6);
+        assert_relative_eq!(top.get(1).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn flags_collapsed_coverage() {
+        let mut dates = vec![day(2); 4];
+        dates.extend([day(3), day(4), day(4), day(4), day(4)]);
+        let symbols = ["A", "B", "C", "D", "A", "A", "B", "C", "D"];
+        let frame = |column: &str| {
+            DataFrame::new(vec![
+                Column::new("date".into(), &dates),
+                Column::new("symbol".into(), symbols),
+                Column::new(column.into(), vec![1.0; 9]),
+            ])
+            .unwrap()
+            .lazy()
+        };
+
+        let report = data_quality_report(
+            frame("asset_returns"),
+            frame("market_cap"),
+            frame("sector_Tech"),
+            frame("mom_score"),
+            &QualityConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(report.low_coverage_dates(0.5).unwrap(), [day(3)]);
+        let exited = report.coverage.column("exited").unwrap().u32().unwrap().to_vec();
+        let entered = report.coverage.column("entered").unwrap().u32().unwrap().to_vec();
+        assert_eq!(exited, [Some(0), Some(3), Some(0)]);
+        assert_eq!(entered, [Some(0), Some(0), Some(3)]);
+    }
+}
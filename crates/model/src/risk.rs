@@ -0,0 +1,547 @@
+//! Factor covariance and specific risk.
+
+use std::collections::BTreeMap;
+
+use ndarray::Array2;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use toraniko_primitives::Date;
+use toraniko_traits::EstimatorError;
+
+use crate::{EstimationFrequency, ModelError};
+
+/// Configuration for factor covariance and specific risk estimation.
+///
+/// Half-lives are measured in periods of `frequency`, so a monthly model
+/// with a 24-period half-life weights two-year-old returns by one half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskConfig {
+    /// Half-life of the factor covariance weights, in periods.
+    pub factor_half_life: usize,
+    /// Half-life of the specific variance weights, in periods.
+    pub specific_half_life: usize,
+    /// Frequency of the factor and residual returns.
+    pub frequency: EstimationFrequency,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self { factor_half_life: 90, specific_half_life: 42, frequency: EstimationFrequency::Daily }
+    }
+}
+
+/// Exponentially weighted factor covariance matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorCovariance {
+    factors: Vec<String>,
+    matrix: Array2<f64>,
+    frequency: EstimationFrequency,
+}
+
+impl FactorCovariance {
+    /// Factor names, in row and column order.
+    #[must_use]
+    pub fn factors(&self) -> &[String] {
+        &self.factors
+    }
+
+    /// Covariance matrix per period of [`Self::frequency`].
+    #[must_use]
+    pub const fn matrix(&self) -> &Array2<f64> {
+        &self.matrix
+    }
+
+    /// Frequency the covariance is expressed in.
+    #[must_use]
+    pub const fn frequency(&self) -> EstimationFrequency {
+        self.frequency
+    }
+
+    /// Variance of a single factor, or `None` for an unknown factor.
+    #[must_use]
+    pub fn variance(&self, factor: &str) -> Option<f64> {
+        self.factors.iter().position(|f| f == factor).map(|i| self.matrix[[i, i]])
+    }
+
+    /// Rescale the covariance to another frequency, e.g. daily to monthly.
+    #[must_use]
+    pub fn to_frequency(&self, target: EstimationFrequency) -> Self {
+        Self {
+            factors: self.factors.clone(),
+            matrix: &self.matrix * self.frequency.variance_scale(target),
+            frequency: target,
+        }
+    }
+
+    /// Annualized covariance matrix.
+    #[must_use]
+    pub fn annualized(&self) -> Array2<f64> {
+        &self.matrix * self.frequency.periods_per_year()
+    }
+
+    /// Long-format frame with one row per factor pair.
+    ///
+    /// # Returns
+    /// DataFrame with | factor_1 | factor_2 | covariance |.
+    ///
+    /// # Errors
+    /// Returns `ModelError::Polars` if the frame cannot be built.
+    pub fn to_frame(&self) -> Result<DataFrame, ModelError> {
+        let k = self.factors.len();
+        let (mut first, mut second) = (Vec::with_capacity(k * k), Vec::with_capacity(k * k));
+        for a in &self.factors {
+            for b in &self.factors {
+                first.push(a.as_str());
+                second.push(b.as_str());
+            }
+        }
+        let values: Vec<f64> = self.matrix.iter().copied().collect();
+
+        Ok(DataFrame::new(vec![
+            Column::new("factor_1".into(), first),
+            Column::new("factor_2".into(), second),
+            Column::new("covariance".into(), values),
+        ])?)
+    }
+
+    /// Rebuild a covariance from the frame produced by [`Self::to_frame`].
+    ///
+    /// # Errors
+    /// Returns `ModelError::MissingColumn` or `ModelError::Polars` for a
+    /// malformed frame, or `ModelError::DimensionMismatch` if a factor pair
+    /// is missing.
+    pub fn from_frame(df: &DataFrame, frequency: EstimationFrequency) -> Result<Self, ModelError> {
+        let column =
+            |name: &str| df.column(name).map_err(|_| ModelError::MissingColumn(name.to_string()));
+        let first = column("factor_1")?.str()?;
+        let second = column("factor_2")?.str()?;
+        let values = column("covariance")?.f64()?;
+
+        let mut factors: Vec<String> = first.into_no_null_iter().map(str::to_string).collect();
+        factors.sort();
+        factors.dedup();
+        let index = |name: Option<&str>| name.and_then(|n| factors.iter().position(|f| f == n));
+
+        let k = factors.len();
+        let mut matrix = Array2::from_elem((k, k), f64::NAN);
+        for ((a, b), value) in first.iter().zip(second.iter()).zip(values.iter()) {
+            if let (Some(i), Some(j), Some(value)) = (index(a), index(b), value) {
+                matrix[[i, j]] = value;
+            }
+        }
+        if matrix.iter().any(|v| v.is_nan()) {
+            return Err(ModelError::DimensionMismatch(format!(
+                "covariance frame does not cover all {k} x {k} factor pairs"
+            )));
+        }
+
+        Ok(Self { factors, matrix, frequency })
+    }
+}
+
+/// Recursive exponentially weighted moments behind the factor covariance
+/// and specific variances.
+///
+/// Each processed date decays the accumulated sums by `0.5^(1 / half_life)`
+/// and adds the new observation, which is equivalent to normalized
+/// exponential weights over the full history. Updating the state with new
+/// dates therefore gives the same estimates as recomputing from scratch, so
+/// the state can be persisted and carried forward by a daily job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskState {
+    config: RiskConfig,
+    /// Factor names, sorted.
+    factors: Vec<String>,
+    /// Number of dates folded into the factor moments.
+    periods: usize,
+    /// Last date folded into the factor moments.
+    last_factor_date: Option<Date>,
+    /// Decayed sum of weights.
+    weight_sum: f64,
+    /// Decayed sum of factor returns, per factor.
+    return_sum: Vec<f64>,
+    /// Decayed sum of factor return cross products, row-major.
+    cross_sum: Vec<f64>,
+    /// Last date folded into the specific moments.
+    last_residual_date: Option<Date>,
+    /// Decayed residual moments per symbol.
+    specific: BTreeMap<String, SpecificMoments>,
+}
+
+/// Decayed residual moments of one symbol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+struct SpecificMoments {
+    weight_sum: f64,
+    square_sum: f64,
+}
+
+/// Per-period decay for a half-life.
+fn decay(half_life: usize) -> Result<f64, ModelError> {
+    if half_life == 0 {
+        return Err(ModelError::InvalidConfig("half-life must be positive".to_string()));
+    }
+    Ok(0.5_f64.powf(1.0 / half_life as f64))
+}
+
+impl RiskState {
+    /// Create an empty state.
+    ///
+    /// # Errors
+    /// Returns `ModelError::InvalidConfig` for a zero half-life.
+    pub fn new(config: RiskConfig) -> Result<Self, ModelError> {
+        decay(config.factor_half_life)?;
+        decay(config.specific_half_life)?;
+        Ok(Self {
+            config,
+            factors: Vec::new(),
+            periods: 0,
+            last_factor_date: None,
+            weight_sum: 0.0,
+            return_sum: Vec::new(),
+            cross_sum: Vec::new(),
+            last_residual_date: None,
+            specific: BTreeMap::new(),
+        })
+    }
+
+    /// Risk configuration of the state.
+    #[must_use]
+    pub const fn config(&self) -> &RiskConfig {
+        &self.config
+    }
+
+    /// Last date of factor returns folded into the state.
+    #[must_use]
+    pub const fn last_date(&self) -> Option<Date> {
+        self.last_factor_date
+    }
+
+    /// Fold new factor returns and residuals into the state.
+    ///
+    /// Only dates after those already processed are used, so passing the
+    /// full history again is harmless. The update is applied to a copy, so
+    /// the state is unchanged on error.
+    ///
+    /// # Arguments
+    /// * `factor_returns` - DataFrame with | date | factor | factor_return |
+    /// * `residuals` - DataFrame with | date | symbol | residual_return |
+    ///
+    /// # Errors
+    /// Returns a polars error for missing or mistyped columns.
+    pub fn update(
+        &mut self,
+        factor_returns: &DataFrame,
+        residuals: &DataFrame,
+    ) -> Result<(), ModelError> {
+        let mut next = self.clone();
+        next.update_factors(factor_returns)?;
+        next.update_specific(residuals)?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Fold new factor returns into the factor moments.
+    ///
+    /// A factor missing on a date (e.g. a sector without members) contributes
+    /// a zero return for that date, including dates before it first appears.
+    /// The `date` column may be a Date or an Int32 count of days since the
+    /// Unix epoch.
+    ///
+    /// # Errors
+    /// Returns a polars error for missing or mistyped columns.
+    pub fn update_factors(&mut self, factor_returns: &DataFrame) -> Result<(), ModelError> {
+        let dates = factor_returns.column("date")?.cast(&DataType::Date)?;
+        let names = factor_returns.column("factor")?;
+        let values = factor_returns.column("factor_return")?;
+
+        let mut by_date: BTreeMap<Date, BTreeMap<&str, f64>> = BTreeMap::new();
+        for ((date, name), value) in
+            dates.date()?.as_date_iter().zip(names.str()?.iter()).zip(values.f64()?.iter())
+        {
+            let (Some(date), Some(name), Some(value)) = (date, name, value) else { continue };
+            if self.last_factor_date.is_none_or(|last| date > last) {
+                by_date.entry(date).or_default().insert(name, value);
+            }
+        }
+
+        let decay = decay(self.config.factor_half_life)?;
+        for (date, row) in by_date {
+            for name in row.keys() {
+                self.add_factor(name);
+            }
+            let k = self.factors.len();
+            let f: Vec<f64> =
+                self.factors.iter().map(|n| row.get(n.as_str()).copied().unwrap_or(0.0)).collect();
+
+            self.weight_sum = decay * self.weight_sum + 1.0;
+            for i in 0..k {
+                self.return_sum[i] = decay * self.return_sum[i] + f[i];
+                for j in 0..k {
+                    self.cross_sum[i * k + j] = decay * self.cross_sum[i * k + j] + f[i] * f[j];
+                }
+            }
+            self.periods += 1;
+            self.last_factor_date = Some(date);
+        }
+
+        Ok(())
+    }
+
+    /// Insert a factor at its sorted position with zero history.
+    fn add_factor(&mut self, name: &str) {
+        let Err(at) = self.factors.binary_search_by(|f| f.as_str().cmp(name)) else { return };
+        let k = self.factors.len();
+        let mut cross = Vec::with_capacity((k + 1) * (k + 1));
+        for i in 0..=k {
+            for j in 0..=k {
+                let old = |x: usize| {
+                    if x < at {
+                        Some(x)
+                    } else if x > at {
+                        Some(x - 1)
+                    } else {
+                        None
+                    }
+                };
+                cross.push(match (old(i), old(j)) {
+                    (Some(a), Some(b)) => self.cross_sum[a * k + b],
+                    _ => 0.0,
+                });
+            }
+        }
+        self.factors.insert(at, name.to_string());
+        self.return_sum.insert(at, 0.0);
+        self.cross_sum = cross;
+    }
+
+    /// Fold new residuals into the per-symbol specific moments.
+    ///
+    /// Each symbol's residuals are weighted by recency within its own history
+    /// and assumed to have zero mean. The `date` column may be a Date or an
+    /// Int32 count of days since the Unix epoch.
+    ///
+    /// # Errors
+    /// Returns a polars error for missing or mistyped columns.
+    pub fn update_specific(&mut self, residuals: &DataFrame) -> Result<(), ModelError> {
+        let sorted =
+            residuals.sort(["date"], SortMultipleOptions::default().with_maintain_order(true))?;
+        let dates = sorted.column("date")?.cast(&DataType::Date)?;
+        let symbols = sorted.column("symbol")?;
+        let values = sorted.column("residual_return")?;
+
+        let decay = decay(self.config.specific_half_life)?;
+        for ((date, symbol), value) in
+            dates.date()?.as_date_iter().zip(symbols.str()?.iter()).zip(values.f64()?.iter())
+        {
+            let (Some(date), Some(symbol), Some(value)) = (date, symbol, value) else { continue };
+            if self.last_residual_date.is_some_and(|last| date <= last) {
+                continue;
+            }
+            let moments = self.specific.entry(symbol.to_string()).or_default();
+            moments.weight_sum = decay * moments.weight_sum + 1.0;
+            moments.square_sum = decay * moments.square_sum + value * value;
+        }
+        // Advance only after the loop so every row of the newest date is used
+        let newest = dates.date()?.as_date_iter().flatten().max();
+        if newest > self.last_residual_date {
+            self.last_residual_date = newest;
+        }
+
+        Ok(())
+    }
+
+    /// Factor covariance per period from the accumulated moments.
+    ///
+    /// Computed in one pass as `E[ff'] - E[f]E[f]'`, so the state never needs
+    /// the return history. This loses precision when a factor's mean is large
+    /// relative to its volatility; for factor returns, whose means are a small
+    /// fraction of their daily volatility, the error is negligible next to the
+    /// estimation noise. [`factor_covariance`] shares this computation so batch
+    /// and incremental estimates agree exactly.
+    ///
+    /// # Errors
+    /// Returns an insufficient data error with fewer than two dates.
+    pub fn covariance(&self) -> Result<FactorCovariance, ModelError> {
+        if self.periods < 2 {
+            return Err(
+                EstimatorError::InsufficientData { required: 2, actual: self.periods }.into()
+            );
+        }
+
+        let k = self.factors.len();
+        let mean: Vec<f64> = self.return_sum.iter().map(|s| s / self.weight_sum).collect();
+        let matrix = Array2::from_shape_fn((k, k), |(i, j)| {
+            self.cross_sum[i * k + j] / self.weight_sum - mean[i] * mean[j]
+        });
+
+        Ok(FactorCovariance {
+            factors: self.factors.clone(),
+            matrix,
+            frequency: self.config.frequency,
+        })
+    }
+
+    /// Specific variance per period for every symbol seen so far.
+    ///
+    /// # Returns
+    /// DataFrame with | symbol | specific_variance |, sorted by symbol.
+    ///
+    /// # Errors
+    /// Returns a polars error if the frame cannot be built.
+    pub fn specific_variance(&self) -> Result<DataFrame, ModelError> {
+        let names: Vec<&str> = self.specific.keys().map(String::as_str).collect();
+        let variances: Vec<f64> =
+            self.specific.values().map(|m| m.square_sum / m.weight_sum).collect();
+
+        Ok(DataFrame::new(vec![
+            Column::new("symbol".into(), names),
+            Column::new("specific_variance".into(), variances),
+        ])?)
+    }
+}
+
+/// Estimate an exponentially weighted factor covariance matrix.
+///
+/// A factor missing on a date (e.g. a sector without members) contributes a
+/// zero return for that date. Equivalent to folding the factor returns into
+/// a fresh [`RiskState`].
+///
+/// # Arguments
+/// * `factor_returns` - DataFrame with | date | factor | factor_return |, as
+///   returned by the estimator
+/// * `config` - Half-life and frequency of the factor returns
+///
+/// # Returns
+/// Covariance per period, with factors in sorted order.
+///
+/// # Errors
+/// Returns `ModelError::InvalidConfig` for a zero half-life, or an
+/// insufficient data error with fewer than two dates.
+pub fn factor_covariance(
+    factor_returns: &DataFrame,
+    config: &RiskConfig,
+) -> Result<FactorCovariance, ModelError> {
+    let mut state = RiskState::new(*config)?;
+    state.update_factors(factor_returns)?;
+    state.covariance()
+}
+
+/// Estimate exponentially weighted specific variances per symbol.
+///
+/// Each symbol's residuals are weighted by recency within its own history
+/// and assumed to have zero mean.
+///
+/// # Arguments
+/// * `residuals` - DataFrame with | date | symbol | residual_return |
+/// * `config` - Half-life and frequency of the residual returns
+///
+/// # Returns
+/// DataFrame with | symbol | specific_variance | (per period), sorted by symbol.
+///
+/// # Errors
+/// Returns `ModelError::InvalidConfig` for a zero half-life, or a polars
+/// error for missing columns.
+pub fn specific_variance(
+    residuals: &DataFrame,
+    config: &RiskConfig,
+) -> Result<DataFrame, ModelError> {
+    let mut state = RiskState::new(*config)?;
+    state.update_specific(residuals)?;
+    state.specific_variance()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn covariance_matches_sample_moments() {
+        let dates: Vec<Date> =
+            (1..=4).map(|d| Date::from_ymd_opt(2024, 1, d).unwrap()).flat_map(|d| [d, d]).collect();
+        let factor_returns = df! {
+            "date" => &dates,
+            "factor" => ["market", "sector_Tech"].repeat(4),
+            "factor_return" => &[0.01, 0.02, -0.01, -0.01, 0.02, 0.03, 0.0, -0.02],
+        }
+        .unwrap();
+
+        // A very long half-life approaches equal weights
+        let config = RiskConfig { factor_half_life: 1_000_000, ..Default::default() };
+        let cov = factor_covariance(&factor_returns, &config).unwrap();
+
+        assert_eq!(cov.factors(), ["market", "sector_Tech"]);
+        let (market, tech) = ([0.01, -0.01, 0.02, 0.0], [0.02, -0.01, 0.03, -0.02]);
+        let mean = |x: &[f64]| x.iter().sum::<f64>() / 4.0;
+        let cross =
+            (0..4).map(|i| (market[i] - mean(&market)) * (tech[i] - mean(&tech))).sum::<f64>()
+                / 4.0;
+        assert_relative_eq!(cov.matrix()[[0, 1]], cross, epsilon = 1e-9);
+        assert_relative_eq!(cov.matrix()[[1, 0]], cross, epsilon = 1e-9);
+
+        let monthly = cov.to_frequency(EstimationFrequency::Monthly);
+        assert_relative_eq!(monthly.matrix()[[0, 1]], cross * 21.0, epsilon = 1e-9);
+        assert_relative_eq!(cov.annualized()[[0, 0]], cov.variance("market").unwrap() * 252.0);
+
+        let restored =
+            FactorCovariance::from_frame(&cov.to_frame().unwrap(), cov.frequency()).unwrap();
+        assert_eq!(restored, cov);
+    }
+
+    #[test]
+    fn accepts_integer_dates() {
+        let days = [1_i32, 1, 2, 2, 3, 3];
+        let factor_returns = df! {
+            "date" => &days,
+            "factor" => ["market", "sector_Tech"].repeat(3),
+            "factor_return" => &[0.01, 0.02, -0.01, -0.01, 0.02, 0.03],
+        }
+        .unwrap();
+        let mut dated = factor_returns.clone();
+        dated.apply("date", |c| c.cast(&DataType::Date).unwrap()).unwrap();
+
+        let config = RiskConfig::default();
+        assert_eq!(
+            factor_covariance(&factor_returns, &config).unwrap(),
+            factor_covariance(&dated, &config).unwrap()
+        );
+    }
+
+    #[test]
+    fn failed_update_leaves_state_unchanged() {
+        let d = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let factor_returns =
+            df! { "date" => [d], "factor" => ["market"], "factor_return" => [0.01] }.unwrap();
+        let residuals = df! { "date" => [d], "symbol" => ["A"] }.unwrap();
+
+        let mut state = RiskState::new(RiskConfig::default()).unwrap();
+        assert!(state.update(&factor_returns, &residuals).is_err());
+        assert_eq!(state, RiskState::new(RiskConfig::default()).unwrap());
+    }
+
+    #[test]
+    fn specific_variance_weights_recent_residuals() {
+        let dates: Vec<Date> = (1..=3).map(|d| Date::from_ymd_opt(2024, 1, d).unwrap()).collect();
+        let residuals = df! {
+            "date" => &dates,
+            "symbol" => &["A", "A", "A"],
+            "residual_return" => &[0.0, 0.0, 0.02],
+        }
+        .unwrap();
+
+        let config = RiskConfig { specific_half_life: 1, ..Default::default() };
+        let result = specific_variance(&residuals, &config).unwrap();
+        let variance = result.column("specific_variance").unwrap().f64().unwrap().get(0).unwrap();
+
+        // Weights 1/7, 2/7, 4/7 from oldest to newest
+        assert_relative_eq!(variance, 4.0 / 7.0 * 0.0004, epsilon = 1e-12);
+        assert!(matches!(
+            specific_variance(
+                &residuals,
+                &RiskConfig { specific_half_life: 0, ..Default::default() }
+            ),
+            Err(ModelError::InvalidConfig(_))
+        ));
+    }
+}
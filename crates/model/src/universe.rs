@@ -0,0 +1,133 @@
+//! Estimation universe selection.
+//!
+//! Factor returns are fit on an estimation universe of liquid, investable
+//! names, while exposures and specific returns are reported for the wider
+//! coverage universe using those factor returns.
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use toraniko_utils::top_n_by_group;
+
+/// Rule selecting the estimation universe on each date.
+///
+/// Columns referenced by a rule must be present in one of the frames passed
+/// to the estimator (e.g. a liquidity column alongside `market_cap`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum EstimationUniverse {
+    /// Every asset in the coverage universe.
+    #[default]
+    All,
+    /// The `n` largest assets per date by `column` (e.g. `market_cap`).
+    TopN {
+        /// Number of assets per date.
+        n: u32,
+        /// Column to rank by, descending.
+        column: String,
+    },
+    /// Assets whose `column` is at least `threshold` (e.g. average daily volume).
+    MinValue {
+        /// Column to filter on.
+        column: String,
+        /// Inclusive lower bound.
+        threshold: f64,
+    },
+    /// Assets whose boolean flag column is true.
+    Flag(String),
+}
+
+impl EstimationUniverse {
+    /// Returns true if every asset is in the estimation universe.
+    #[must_use]
+    pub const fn is_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+
+    /// Add a boolean column `name` marking estimation universe members.
+    ///
+    /// Rows with null rule inputs are excluded from the estimation universe.
+    ///
+    /// # Arguments
+    /// * `df` - LazyFrame with | date | symbol | and the columns used by the rule
+    /// * `name` - Name of the membership column to add
+    ///
+    /// # Returns
+    /// LazyFrame with all input rows and the membership column.
+    pub fn mark(&self, df: LazyFrame, name: &str) -> LazyFrame {
+        match self {
+            Self::All => df.with_column(lit(true).alias(name)),
+            Self::TopN { n, column } => {
+                let members = top_n_by_group(
+                    df.clone().filter(col(column.as_str()).is_not_null()),
+                    *n,
+                    column,
+                    &["date"],
+                    true,
+                )
+                .select([col("date"), col("symbol"), lit(true).alias(name)]);
+                df.join(
+                    members,
+                    [col("date"), col("symbol")],
+                    [col("date"), col("symbol")],
+                    JoinArgs::new(JoinType::Left),
+                )
+                .with_column(col(name).fill_null(lit(false)))
+            }
+            Self::MinValue { column, threshold } => df.with_column(
+                col(column.as_str()).gt_eq(lit(*threshold)).fill_null(lit(false)).alias(name),
+            ),
+            Self::Flag(column) => df.with_column(
+                col(column.as_str()).cast(DataType::Boolean).fill_null(lit(false)).alias(name),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> LazyFrame {
+        df! {
+            "date" => &[1, 1, 1, 2, 2, 2],
+            "symbol" => &["A", "B", "C", "A", "B", "C"],
+            "market_cap" => &[Some(3.0), Some(2.0), Some(1.0), Some(1.0), None, Some(3.0)],
+            "liquid" => &[true, false, true, true, true, false],
+        }
+        .unwrap()
+        .lazy()
+    }
+
+    fn members(universe: &EstimationUniverse) -> Vec<(i32, String)> {
+        let df = universe
+            .mark(frame(), "estu")
+            .filter(col("estu"))
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let dates = df.column("date").unwrap().i32().unwrap();
+        let symbols = df.column("symbol").unwrap().str().unwrap();
+        dates
+            .into_no_null_iter()
+            .zip(symbols.into_no_null_iter())
+            .map(|(d, s)| (d, s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn top_n_per_date() {
+        let universe = EstimationUniverse::TopN { n: 2, column: "market_cap".to_string() };
+        let expected = vec![(1, "A".into()), (1, "B".into()), (2, "A".into()), (2, "C".into())];
+        assert_eq!(members(&universe), expected);
+    }
+
+    #[test]
+    fn threshold_and_flag() {
+        let universe =
+            EstimationUniverse::MinValue { column: "market_cap".to_string(), threshold: 2.0 };
+        assert_eq!(members(&universe), vec![(1, "A".into()), (1, "B".into()), (2, "C".into())]);
+
+        let universe = EstimationUniverse::Flag("liquid".to_string());
+        assert_eq!(members(&universe).len(), 4);
+        assert_eq!(members(&EstimationUniverse::All).len(), 6);
+    }
+}
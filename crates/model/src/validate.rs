@@ -0,0 +1,405 @@
+//! Upfront validation of estimator inputs.
+
+use std::fmt;
+
+use polars::prelude::*;
+
+use crate::{MEMBERSHIP_TOLERANCE, ModelError};
+
+/// One of the estimator input frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputFrame {
+    /// | date | symbol | asset_returns |
+    Returns,
+    /// | date | symbol | market_cap |
+    MarketCaps,
+    /// | date | symbol | sector_* |
+    Sectors,
+    /// | date | symbol | *_score |
+    Styles,
+}
+
+impl InputFrame {
+    /// Named f64 columns the frame must contain.
+    const fn required_columns(self) -> &'static [&'static str] {
+        match self {
+            Self::Returns => &["asset_returns"],
+            Self::MarketCaps => &["market_cap"],
+            Self::Sectors | Self::Styles => &[],
+        }
+    }
+
+    /// Whether a column is one of the frame's f64 exposure columns.
+    fn is_exposure_column(self, name: &str) -> bool {
+        match self {
+            Self::Sectors => name.starts_with("sector_"),
+            Self::Styles => name.ends_with("_score"),
+            Self::Returns | Self::MarketCaps => false,
+        }
+    }
+}
+
+impl fmt::Display for InputFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Returns => "returns",
+            Self::MarketCaps => "market caps",
+            Self::Sectors => "sectors",
+            Self::Styles => "styles",
+        })
+    }
+}
+
+/// A single problem found in the estimator inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    /// A required column is absent.
+    MissingColumn {
+        /// Input frame.
+        frame: InputFrame,
+        /// Column name.
+        column: String,
+    },
+    /// A column has the wrong type.
+    WrongType {
+        /// Input frame.
+        frame: InputFrame,
+        /// Column name.
+        column: String,
+        /// Expected type.
+        expected: DataType,
+        /// Actual type.
+        found: DataType,
+    },
+    /// Rows with a null `date` or `symbol`.
+    NullKeys {
+        /// Input frame.
+        frame: InputFrame,
+        /// Number of rows.
+        rows: usize,
+    },
+    /// `date, symbol` keys appearing more than once.
+    DuplicateKeys {
+        /// Input frame.
+        frame: InputFrame,
+        /// Number of repeated keys.
+        keys: usize,
+    },
+    /// Negative or null market capitalizations.
+    InvalidMarketCaps {
+        /// Number of rows.
+        rows: usize,
+    },
+    /// The sector frame has no `sector_*` columns.
+    NoSectorColumns,
+    /// Sector rows that are negative or do not sum to one.
+    SectorRowSums {
+        /// Number of rows.
+        rows: usize,
+    },
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn { frame, column } => write!(f, "{frame}: missing column {column}"),
+            Self::WrongType { frame, column, expected, found } => {
+                write!(f, "{frame}: column {column} is {found}, expected {expected}")
+            }
+            Self::NullKeys { frame, rows } => {
+                write!(f, "{frame}: {rows} rows with a null date or symbol")
+            }
+            Self::DuplicateKeys { frame, keys } => {
+                write!(f, "{frame}: {keys} duplicate date, symbol keys")
+            }
+            Self::InvalidMarketCaps { rows } => {
+                write!(f, "market caps: {rows} negative or null values")
+            }
+            Self::NoSectorColumns => write!(f, "sectors: no sector_* columns"),
+            Self::SectorRowSums { rows } => {
+                write!(f, "sectors: {rows} rows are negative or do not sum to one")
+            }
+        }
+    }
+}
+
+/// Every violation found by [`validate_inputs`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Violations in the order they were found.
+    pub violations: Vec<SchemaViolation>,
+}
+
+impl ValidationReport {
+    /// Check whether the inputs are valid.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Convert into a result, failing with `ModelError::InvalidInput` if any
+    /// violation was found.
+    ///
+    /// # Errors
+    /// Returns `ModelError::InvalidInput` with this report.
+    pub fn into_result(self) -> Result<(), ModelError> {
+        if self.is_valid() { Ok(()) } else { Err(ModelError::InvalidInput(self)) }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} violation(s)", self.violations.len())?;
+        for violation in &self.violations {
+            write!(f, "\n  - {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the estimator inputs against the expected schema.
+///
+/// Checks every frame for `date` (Date) and `symbol` (String) keys that are
+/// non-null and unique, `asset_returns`, `market_cap`, `sector_*` and
+/// `*_score` columns of type f64, non-negative market caps, and sector rows
+/// that are non-negative and sum to one. Data checks run only on frames
+/// whose columns have the expected types.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+/// * `sector_df` - LazyFrame with | date | symbol | sector_* |
+/// * `style_df` - LazyFrame with | date | symbol | *_score |
+///
+/// # Returns
+/// A report listing every violation; empty if the inputs are valid.
+///
+/// # Errors
+/// Returns `ModelError::Polars` if a frame cannot be evaluated.
+pub fn validate_inputs(
+    returns_df: &LazyFrame,
+    mkt_cap_df: &LazyFrame,
+    sector_df: &LazyFrame,
+    style_df: &LazyFrame,
+) -> Result<ValidationReport, ModelError> {
+    let mut violations = Vec::new();
+
+    let frames = [
+        (InputFrame::Returns, returns_df),
+        (InputFrame::MarketCaps, mkt_cap_df),
+        (InputFrame::Sectors, sector_df),
+        (InputFrame::Styles, style_df),
+    ];
+    for (frame, lf) in frames {
+        let schema = lf.clone().collect_schema()?;
+        let before = violations.len();
+
+        for (column, expected) in [("date", DataType::Date), ("symbol", DataType::String)] {
+            check_column(&mut violations, frame, &schema, column, &expected);
+        }
+        for column in frame.required_columns() {
+            check_column(&mut violations, frame, &schema, column, &DataType::Float64);
+        }
+        for column in schema.iter_names().filter(|name| frame.is_exposure_column(name)) {
+            check_column(&mut violations, frame, &schema, column, &DataType::Float64);
+        }
+        if frame == InputFrame::Sectors && !schema.iter_names().any(|n| frame.is_exposure_column(n))
+        {
+            violations.push(SchemaViolation::NoSectorColumns);
+        }
+        if violations.len() > before {
+            continue;
+        }
+
+        check_keys(&mut violations, frame, lf)?;
+        match frame {
+            InputFrame::MarketCaps => check_market_caps(&mut violations, lf)?,
+            InputFrame::Sectors => check_sector_rows(&mut violations, lf, &schema)?,
+            InputFrame::Returns | InputFrame::Styles => {}
+        }
+    }
+
+    Ok(ValidationReport { violations })
+}
+
+fn check_column(
+    violations: &mut Vec<SchemaViolation>,
+    frame: InputFrame,
+    schema: &Schema,
+    column: &str,
+    expected: &DataType,
+) {
+    match schema.get(column) {
+        None => {
+            violations.push(SchemaViolation::MissingColumn { frame, column: column.to_string() })
+        }
+        Some(found) if found != expected => violations.push(SchemaViolation::WrongType {
+            frame,
+            column: column.to_string(),
+            expected: expected.clone(),
+            found: found.clone(),
+        }),
+        Some(_) => {}
+    }
+}
+
+fn check_market_caps(
+    violations: &mut Vec<SchemaViolation>,
+    lf: &LazyFrame,
+) -> Result<(), ModelError> {
+    let rows = count(lf, col("market_cap").is_null().or(col("market_cap").lt(lit(0.0))))?;
+    if rows > 0 {
+        violations.push(SchemaViolation::InvalidMarketCaps { rows });
+    }
+    Ok(())
+}
+
+fn check_sector_rows(
+    violations: &mut Vec<SchemaViolation>,
+    lf: &LazyFrame,
+    schema: &Schema,
+) -> Result<(), ModelError> {
+    let sectors: Vec<Expr> = schema
+        .iter_names()
+        .filter(|n| InputFrame::Sectors.is_exposure_column(n))
+        .map(|n| col(n.clone()).fill_null(lit(0.0)))
+        .collect();
+    let negative = sectors
+        .iter()
+        .map(|e| e.clone().lt(lit(0.0)))
+        .reduce(Expr::or)
+        .unwrap_or_else(|| lit(false));
+    let total = sectors.into_iter().reduce(|a, b| a + b).unwrap_or_else(|| lit(0.0));
+    let off = (total.clone() - lit(1.0))
+        .gt(lit(MEMBERSHIP_TOLERANCE))
+        .or((lit(1.0) - total).gt(lit(MEMBERSHIP_TOLERANCE)));
+    let rows = count(lf, negative.or(off))?;
+    if rows > 0 {
+        violations.push(SchemaViolation::SectorRowSums { rows });
+    }
+    Ok(())
+}
+
+fn check_keys(
+    violations: &mut Vec<SchemaViolation>,
+    frame: InputFrame,
+    lf: &LazyFrame,
+) -> Result<(), ModelError> {
+    let rows = count(lf, col("date").is_null().or(col("symbol").is_null()))?;
+    if rows > 0 {
+        violations.push(SchemaViolation::NullKeys { frame, rows });
+    }
+
+    let duplicates = lf
+        .clone()
+        .group_by([col("date"), col("symbol")])
+        .agg([len().alias("_rows")])
+        .filter(col("_rows").gt(lit(1)))
+        .select([len()])
+        .collect()?;
+    let keys = duplicates.column("len")?.u32()?.get(0).unwrap_or(0) as usize;
+    if keys > 0 {
+        violations.push(SchemaViolation::DuplicateKeys { frame, keys });
+    }
+    Ok(())
+}
+
+/// Number of rows matching a predicate.
+fn count(lf: &LazyFrame, predicate: Expr) -> Result<usize, ModelError> {
+    let df = lf.clone().select([predicate.cast(DataType::UInt32).sum().alias("n")]).collect()?;
+    Ok(df.column("n")?.u32()?.get(0).unwrap_or(0) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use toraniko_primitives::Date;
+
+    use super::*;
+
+    #[test]
+    fn reports_every_violation() {
+        let d = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let returns = df! {
+            "date" => [d, d, d],
+            "symbol" => &["A", "A", "B"],
+            "asset_returns" => &[0.01, 0.02, 0.03],
+        }
+        .unwrap()
+        .lazy();
+        let mkt_caps = df! {
+            "date" => [d, d],
+            "symbol" => &[Some("A"), None],
+            "market_cap" => &[-1.0, 2.0],
+        }
+        .unwrap()
+        .lazy();
+        let sectors = df! {
+            "date" => [d, d],
+            "symbol" => &["A", "B"],
+            "sector_Tech" => &[1.0, 0.6],
+            "sector_Other" => &[0.0, 0.3],
+        }
+        .unwrap()
+        .lazy();
+        let styles = df! {
+            "date" => [d, d],
+            "symbol" => &["A", "B"],
+            "mom_score" => &[1_i64, 2],
+        }
+        .unwrap()
+        .lazy();
+
+        let report = validate_inputs(&returns, &mkt_caps, &sectors, &styles).unwrap();
+        assert_eq!(
+            report.violations,
+            vec![
+                SchemaViolation::DuplicateKeys { frame: InputFrame::Returns, keys: 1 },
+                SchemaViolation::NullKeys { frame: InputFrame::MarketCaps, rows: 1 },
+                SchemaViolation::InvalidMarketCaps { rows: 1 },
+                SchemaViolation::SectorRowSums { rows: 1 },
+                SchemaViolation::WrongType {
+                    frame: InputFrame::Styles,
+                    column: "mom_score".to_string(),
+                    expected: DataType::Float64,
+                    found: DataType::Int64,
+                },
+            ]
+        );
+        let message = report.into_result().unwrap_err().to_string();
+        assert!(message.contains("5 violation(s)") && message.contains("mom_score"));
+    }
+
+    #[test]
+    fn accepts_valid_inputs() {
+        let d = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let frame = |column: &str, values: &[f64]| {
+            DataFrame::new(vec![
+                Column::new("date".into(), [d, d]),
+                Column::new("symbol".into(), ["A", "B"]),
+                Column::new(column.into(), values),
+            ])
+            .unwrap()
+            .lazy()
+        };
+
+        let report = validate_inputs(
+            &frame("asset_returns", &[0.01, -0.02]),
+            &frame("market_cap", &[1e9, 2e9]),
+            &frame("sector_Tech", &[1.0, 1.0]),
+            &frame("val_score", &[0.5, -0.5]),
+        )
+        .unwrap();
+        assert!(report.is_valid());
+        assert!(report.into_result().is_ok());
+
+        let report = validate_inputs(
+            &frame("returns", &[0.01, -0.02]),
+            &frame("market_cap", &[1e9, 2e9]),
+            &frame("industry", &[1.0, 1.0]),
+            &frame("val_score", &[0.5, -0.5]),
+        )
+        .unwrap();
+        assert_eq!(report.violations.len(), 2);
+        assert_eq!(report.violations[1], SchemaViolation::NoSectorColumns);
+    }
+}
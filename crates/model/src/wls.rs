@@ -1,15 +1,17 @@
 //! Weighted least squares factor estimation.
 
 use ndarray::{Array1, Array2};
-use toraniko_math::{ConstrainedWlsResult, constrained_wls, winsorize};
+use toraniko_math::{ConstrainedWlsResult, constrained_wls_with_countries, winsorize};
 use toraniko_traits::{EstimatorError, FactorEstimator};
 
+use crate::orthogonalize::orthogonalize_styles;
+
 /// Configuration for WLS estimator.
 #[derive(Debug, Clone)]
 pub struct WlsConfig {
     /// Winsorization percentile for returns (None to disable).
     pub winsor_factor: Option<f64>,
-    /// Whether to residualize styles to sectors.
+    /// Whether to orthogonalize styles to sectors before the regression.
     pub residualize_styles: bool,
 }
 
@@ -45,30 +47,34 @@ impl WlsFactorEstimator {
     pub const fn residualize_styles(&self) -> bool {
         self.config.residualize_styles
     }
-}
-
-impl Default for WlsFactorEstimator {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl FactorEstimator for WlsFactorEstimator {
-    type Config = WlsConfig;
 
-    fn with_config(config: Self::Config) -> Self {
-        Self { config }
-    }
-
-    fn estimate_single(
+    /// Estimate factor returns for a single period with a country factor block.
+    ///
+    /// Country returns are constrained to sum to zero, like sector returns.
+    ///
+    /// # Arguments
+    /// * `returns` - Asset returns (n_assets,)
+    /// * `weights` - Market cap weights (n_assets,)
+    /// * `sector_scores` - Sector exposure matrix (n_assets x n_sectors)
+    /// * `country_scores` - Country exposure matrix (n_assets x n_countries)
+    /// * `style_scores` - Style exposure matrix (n_assets x n_styles)
+    ///
+    /// # Returns
+    /// Tuple of (factor_returns ordered as [market, sectors, countries, styles], residuals)
+    ///
+    /// # Errors
+    /// Returns `EstimatorError` if dimensions mismatch or computation fails.
+    pub fn estimate_single_with_countries(
         &self,
         returns: &Array1<f64>,
         weights: &Array1<f64>,
         sector_scores: &Array2<f64>,
+        country_scores: &Array2<f64>,
         style_scores: &Array2<f64>,
     ) -> Result<(Array1<f64>, Array1<f64>), EstimatorError> {
         let n = returns.len();
         let n_sectors = sector_scores.ncols();
+        let n_countries = country_scores.ncols();
         let n_styles = style_scores.ncols();
 
         // Validate dimensions
@@ -88,6 +94,14 @@ impl FactorEstimator for WlsFactorEstimator {
             });
         }
 
+        if country_scores.nrows() != n {
+            return Err(EstimatorError::DimensionMismatch {
+                expected: n,
+                actual: country_scores.nrows(),
+                context: "country_scores".to_string(),
+            });
+        }
+
         if style_scores.nrows() != n {
             return Err(EstimatorError::DimensionMismatch {
                 expected: n,
@@ -103,29 +117,76 @@ impl FactorEstimator for WlsFactorEstimator {
             returns.clone()
         };
 
+        // Orthogonalize each style to the sectors with the regression weights
+        let mut style_scores = style_scores.clone();
+        if self.config.residualize_styles {
+            let rows: Vec<usize> = (0..n).collect();
+            orthogonalize_styles(&mut style_scores, sector_scores, weights, &rows, &[], true)
+                .map_err(|e| EstimatorError::LinearAlgebra(e.to_string()))?;
+        }
+
         // Compute sqrt market cap weights
         let sqrt_weights: Array1<f64> = weights.mapv(|x| x.max(0.0).sqrt());
 
         // Perform constrained WLS
-        let result: ConstrainedWlsResult =
-            constrained_wls(&returns_clean, &sqrt_weights, sector_scores, style_scores)
-                .map_err(|e| EstimatorError::LinearAlgebra(e.to_string()))?;
+        let result: ConstrainedWlsResult = constrained_wls_with_countries(
+            &returns_clean,
+            &sqrt_weights,
+            sector_scores,
+            country_scores,
+            &style_scores,
+        )
+        .map_err(|e| EstimatorError::LinearAlgebra(e.to_string()))?;
 
         // Combine into single factor returns array
-        // Order: [market, sectors..., styles...]
-        let mut factor_returns = Array1::zeros(1 + n_sectors + n_styles);
+        // Order: [market, sectors..., countries..., styles...]
+        let mut factor_returns = Array1::zeros(1 + n_sectors + n_countries + n_styles);
         factor_returns[0] = result.market_return;
         for (i, &r) in result.sector_returns.iter().enumerate() {
             factor_returns[1 + i] = r;
         }
-        for (i, &r) in result.style_returns.iter().enumerate() {
+        for (i, &r) in result.country_returns.iter().enumerate() {
             factor_returns[1 + n_sectors + i] = r;
         }
+        for (i, &r) in result.style_returns.iter().enumerate() {
+            factor_returns[1 + n_sectors + n_countries + i] = r;
+        }
 
         Ok((factor_returns, result.residuals))
     }
 }
 
+impl Default for WlsFactorEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FactorEstimator for WlsFactorEstimator {
+    type Config = WlsConfig;
+
+    fn with_config(config: Self::Config) -> Self {
+        Self { config }
+    }
+
+    fn estimate_single(
+        &self,
+        returns: &Array1<f64>,
+        weights: &Array1<f64>,
+        sector_scores: &Array2<f64>,
+        style_scores: &Array2<f64>,
+    ) -> Result<(Array1<f64>, Array1<f64>), EstimatorError> {
+        let no_countries = Array2::zeros((returns.len(), 0));
+        self.estimate_single_with_countries(
+            returns,
+            weights,
+            sector_scores,
+            &no_countries,
+            style_scores,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use approx::assert_relative_eq;
@@ -0,0 +1,337 @@
+//! Trading calendar.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::Path,
+};
+
+use chrono::{Datelike, Days, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::Date;
+
+/// Errors raised while loading a [`Calendar`].
+#[derive(Debug, thiserror::Error)]
+pub enum CalendarError {
+    /// The holiday file could not be read.
+    #[error("failed to read holiday file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line of the holiday file is not a `YYYY-MM-DD` date.
+    #[error("invalid holiday on line {line}: {value}")]
+    InvalidDate {
+        /// One-based line number.
+        line: usize,
+        /// Offending text.
+        value: String,
+    },
+
+    /// Every day of the week is a weekend day.
+    #[error("calendar has no business days: every day of the week is a weekend day")]
+    NoBusinessDays,
+}
+
+/// Calendar period used for period-end resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CalendarPeriod {
+    /// Monday to Sunday week.
+    Week,
+    /// Calendar month.
+    Month,
+    /// Calendar quarter.
+    Quarter,
+    /// Calendar year.
+    Year,
+}
+
+/// Trading calendar of weekend days and holidays.
+///
+/// Every day that is neither a weekend day nor a holiday is a business day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "CalendarData")]
+pub struct Calendar {
+    /// Non-trading days of the week; never all seven.
+    weekend: HashSet<Weekday>,
+    /// Non-trading dates.
+    holidays: BTreeSet<Date>,
+}
+
+/// Serialized form of a [`Calendar`], validated on deserialization.
+#[derive(Deserialize)]
+struct CalendarData {
+    weekend: HashSet<Weekday>,
+    holidays: BTreeSet<Date>,
+}
+
+impl TryFrom<CalendarData> for Calendar {
+    type Error = CalendarError;
+
+    fn try_from(data: CalendarData) -> Result<Self, Self::Error> {
+        Self::weekdays().with_holidays(data.holidays).with_weekend(data.weekend)
+    }
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::weekdays()
+    }
+}
+
+impl Calendar {
+    /// Calendar with Saturday and Sunday weekends and no holidays.
+    #[must_use]
+    pub fn weekdays() -> Self {
+        Self { weekend: HashSet::from([Weekday::Sat, Weekday::Sun]), holidays: BTreeSet::new() }
+    }
+
+    /// Add holidays to the calendar.
+    #[must_use]
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = Date>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    /// Replace the weekend days; repeated days are ignored.
+    ///
+    /// # Errors
+    /// Returns `CalendarError::NoBusinessDays` if the weekend covers every day
+    /// of the week.
+    pub fn with_weekend(
+        mut self,
+        weekend: impl IntoIterator<Item = Weekday>,
+    ) -> Result<Self, CalendarError> {
+        self.weekend = weekend.into_iter().collect();
+        if self.weekend.len() == 7 {
+            return Err(CalendarError::NoBusinessDays);
+        }
+        Ok(self)
+    }
+
+    /// Load a weekday calendar with holidays from a text file.
+    ///
+    /// The file holds one `YYYY-MM-DD` date per line; blank lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// # Errors
+    /// Returns `CalendarError::Io` if the file cannot be read, or
+    /// `CalendarError::InvalidDate` for a malformed line.
+    pub fn from_holiday_file(path: impl AsRef<Path>) -> Result<Self, CalendarError> {
+        Self::from_holiday_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a weekday calendar from holiday file contents.
+    ///
+    /// # Errors
+    /// Returns `CalendarError::InvalidDate` for a malformed line.
+    pub fn from_holiday_str(contents: &str) -> Result<Self, CalendarError> {
+        let holidays = contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line, value)| {
+                value
+                    .parse::<Date>()
+                    .map_err(|_| CalendarError::InvalidDate { line, value: value.to_string() })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::weekdays().with_holidays(holidays))
+    }
+
+    /// Holidays in the calendar, in order.
+    pub fn holidays(&self) -> impl Iterator<Item = Date> + '_ {
+        self.holidays.iter().copied()
+    }
+
+    /// Check whether a date is a business day.
+    #[must_use]
+    pub fn is_business_day(&self, date: Date) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date)
+    }
+
+    /// First business day strictly after `date`.
+    #[must_use]
+    pub fn next_business_day(&self, date: Date) -> Date {
+        self.step(date, 1)
+    }
+
+    /// Last business day strictly before `date`.
+    #[must_use]
+    pub fn previous_business_day(&self, date: Date) -> Date {
+        self.step(date, -1)
+    }
+
+    /// `date` if it is a business day, otherwise the next business day.
+    #[must_use]
+    pub fn roll_forward(&self, date: Date) -> Date {
+        if self.is_business_day(date) { date } else { self.next_business_day(date) }
+    }
+
+    /// `date` if it is a business day, otherwise the previous business day.
+    #[must_use]
+    pub fn roll_backward(&self, date: Date) -> Date {
+        if self.is_business_day(date) { date } else { self.previous_business_day(date) }
+    }
+
+    /// Move `n` business days from `date` (backwards for negative `n`).
+    ///
+    /// A non-business `date` counts from the business day before it when
+    /// moving forward and after it when moving backward, so one business day
+    /// after a Saturday is Monday and one before is Friday. For `n == 0` the
+    /// date is rolled forward.
+    #[must_use]
+    pub fn add_business_days(&self, date: Date, n: i64) -> Date {
+        if n == 0 {
+            return self.roll_forward(date);
+        }
+        let direction = n.signum();
+        let mut current = if n < 0 { self.roll_forward(date) } else { self.roll_backward(date) };
+        for _ in 0..n.unsigned_abs() {
+            current = self.step(current, direction);
+        }
+        current
+    }
+
+    /// Business days in `start..=end`, in order.
+    #[must_use]
+    pub fn business_days(&self, start: Date, end: Date) -> Vec<Date> {
+        start.iter_days().take_while(|d| *d <= end).filter(|d| self.is_business_day(*d)).collect()
+    }
+
+    /// Number of business days in `(start, end]`, negative if `end < start`.
+    #[must_use]
+    pub fn business_days_between(&self, start: Date, end: Date) -> i64 {
+        if end < start {
+            return -self.business_days_between(end, start);
+        }
+        start
+            .iter_days()
+            .skip(1)
+            .take_while(|d| *d <= end)
+            .filter(|d| self.is_business_day(*d))
+            .count() as i64
+    }
+
+    /// Last business day of the period containing `date`.
+    ///
+    /// Falls back to the last business day before the period if the period
+    /// has none.
+    #[must_use]
+    pub fn period_end(&self, date: Date, period: CalendarPeriod) -> Date {
+        self.roll_backward(last_day(date, period))
+    }
+
+    /// Check whether `date` is the last business day of its period.
+    #[must_use]
+    pub fn is_period_end(&self, date: Date, period: CalendarPeriod) -> bool {
+        self.is_business_day(date) && self.period_end(date, period) == date
+    }
+
+    /// Last business day of each period touching `start..=end`, in order.
+    #[must_use]
+    pub fn period_ends(&self, start: Date, end: Date, period: CalendarPeriod) -> Vec<Date> {
+        let mut ends: Vec<Date> = self
+            .business_days(start, end)
+            .into_iter()
+            .map(|d| self.period_end(d, period))
+            .collect();
+        ends.dedup();
+        ends
+    }
+
+    fn step(&self, date: Date, direction: i64) -> Date {
+        let mut current = date;
+        loop {
+            current = if direction < 0 {
+                current.checked_sub_days(Days::new(1))
+            } else {
+                current.checked_add_days(Days::new(1))
+            }
+            .expect("date out of range");
+            if self.is_business_day(current) {
+                return current;
+            }
+        }
+    }
+}
+
+/// Last calendar day of the period containing `date`.
+fn last_day(date: Date, period: CalendarPeriod) -> Date {
+    let month_end = |year: i32, month: u32| {
+        let (y, m) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        Date::from_ymd_opt(y, m, 1).and_then(|d| d.pred_opt()).expect("valid month")
+    };
+    match period {
+        CalendarPeriod::Week => {
+            date + Days::new(6 - u64::from(date.weekday().num_days_from_monday()))
+        }
+        CalendarPeriod::Month => month_end(date.year(), date.month()),
+        CalendarPeriod::Quarter => month_end(date.year(), date.month().div_ceil(3) * 3),
+        CalendarPeriod::Year => month_end(date.year(), 12),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> Date {
+        Date::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar() -> Calendar {
+        Calendar::from_holiday_str("# US holidays\n2024-01-01\n\n2024-03-29\n2024-12-25\n").unwrap()
+    }
+
+    #[test]
+    fn business_day_arithmetic() {
+        let cal = calendar();
+        assert!(!cal.is_business_day(date(2024, 1, 1)));
+        assert!(!cal.is_business_day(date(2024, 1, 6)));
+        assert_eq!(cal.next_business_day(date(2023, 12, 29)), date(2024, 1, 2));
+        assert_eq!(cal.previous_business_day(date(2024, 1, 2)), date(2023, 12, 29));
+        assert_eq!(cal.add_business_days(date(2024, 1, 5), 1), date(2024, 1, 8));
+        assert_eq!(cal.add_business_days(date(2024, 1, 6), 1), date(2024, 1, 8));
+        assert_eq!(cal.add_business_days(date(2024, 1, 6), -1), date(2024, 1, 5));
+        assert_eq!(cal.add_business_days(date(2024, 1, 8), -5), date(2023, 12, 29));
+        assert_eq!(cal.add_business_days(date(2024, 1, 6), 0), date(2024, 1, 8));
+        assert_eq!(cal.business_days_between(date(2023, 12, 29), date(2024, 1, 5)), 4);
+        assert_eq!(cal.business_days_between(date(2024, 1, 5), date(2023, 12, 29)), -4);
+        assert_eq!(cal.business_days(date(2024, 1, 1), date(2024, 1, 3)).len(), 2);
+    }
+
+    #[test]
+    fn period_ends() {
+        let cal = calendar();
+        // March 29 2024 is Good Friday and March 30-31 are a weekend
+        assert_eq!(cal.period_end(date(2024, 3, 5), CalendarPeriod::Month), date(2024, 3, 28));
+        assert_eq!(cal.period_end(date(2024, 2, 10), CalendarPeriod::Quarter), date(2024, 3, 28));
+        assert_eq!(cal.period_end(date(2024, 3, 27), CalendarPeriod::Week), date(2024, 3, 28));
+        assert_eq!(cal.period_end(date(2024, 6, 1), CalendarPeriod::Year), date(2024, 12, 31));
+        assert!(cal.is_period_end(date(2024, 3, 28), CalendarPeriod::Month));
+        assert_eq!(
+            cal.period_ends(date(2024, 1, 15), date(2024, 3, 5), CalendarPeriod::Month),
+            vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 28)]
+        );
+    }
+
+    #[test]
+    fn custom_weekend() {
+        use Weekday::{Fri, Mon, Sat, Sun, Thu, Tue, Wed};
+
+        let cal = Calendar::weekdays().with_weekend([Fri, Sat, Fri]).unwrap();
+        assert_eq!(cal.next_business_day(date(2024, 1, 4)), date(2024, 1, 7));
+
+        let cal = Calendar::weekdays().with_weekend([Sat; 7]).unwrap();
+        assert_eq!(cal.next_business_day(date(2024, 1, 5)), date(2024, 1, 7));
+
+        let full = Calendar::weekdays().with_weekend([Mon, Tue, Wed, Thu, Fri, Sat, Sun]);
+        assert!(matches!(full, Err(CalendarError::NoBusinessDays)));
+    }
+
+    #[test]
+    fn invalid_holiday_file() {
+        let err = Calendar::from_holiday_str("2024-01-01\nnot a date\n").unwrap_err();
+        assert!(matches!(err, CalendarError::InvalidDate { line: 2, .. }));
+    }
+}
@@ -0,0 +1,252 @@
+//! Industry classification hierarchy definitions.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Level in a sector → industry group → industry classification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IndustryLevel {
+    /// Coarsest level (e.g. GICS sector).
+    Sector,
+    /// Intermediate level (e.g. GICS industry group).
+    IndustryGroup,
+    /// Finest level (e.g. GICS industry).
+    Industry,
+}
+
+impl IndustryLevel {
+    /// Returns the next coarser level, or `None` at the sector level.
+    #[must_use]
+    pub const fn coarser(self) -> Option<Self> {
+        match self {
+            Self::Sector => None,
+            Self::IndustryGroup => Some(Self::Sector),
+            Self::Industry => Some(Self::IndustryGroup),
+        }
+    }
+}
+
+impl std::fmt::Display for IndustryLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sector => write!(f, "sector"),
+            Self::IndustryGroup => write!(f, "industry_group"),
+            Self::Industry => write!(f, "industry"),
+        }
+    }
+}
+
+/// Three-level industry classification (sector → industry group → industry).
+///
+/// Nodes are identified by their level and name, so an industry group may share
+/// its name with its sector (as happens in GICS).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndustryHierarchy {
+    /// Industry name to industry group name.
+    industries: BTreeMap<String, String>,
+    /// Industry group name to sector name.
+    groups: BTreeMap<String, String>,
+}
+
+impl IndustryHierarchy {
+    /// Create an empty hierarchy.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { industries: BTreeMap::new(), groups: BTreeMap::new() }
+    }
+
+    /// Build a hierarchy from `(sector, industry_group, industry)` triples.
+    #[must_use]
+    pub fn from_triples<S: Into<String>>(triples: impl IntoIterator<Item = (S, S, S)>) -> Self {
+        let mut hierarchy = Self::new();
+        for (sector, group, industry) in triples {
+            hierarchy.insert(sector, group, industry);
+        }
+        hierarchy
+    }
+
+    /// Insert an industry together with its industry group and sector.
+    ///
+    /// Re-inserting an existing industry or group overwrites its parent.
+    pub fn insert(
+        &mut self,
+        sector: impl Into<String>,
+        group: impl Into<String>,
+        industry: impl Into<String>,
+    ) {
+        let group = group.into();
+        self.groups.insert(group.clone(), sector.into());
+        self.industries.insert(industry.into(), group);
+    }
+
+    /// Number of industries (leaf nodes).
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.industries.len()
+    }
+
+    /// Check if empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.industries.is_empty()
+    }
+
+    /// Check whether a node exists at the given level.
+    #[must_use]
+    pub fn contains(&self, level: IndustryLevel, name: &str) -> bool {
+        match level {
+            IndustryLevel::Industry => self.industries.contains_key(name),
+            IndustryLevel::IndustryGroup => self.groups.contains_key(name),
+            IndustryLevel::Sector => self.groups.values().any(|s| s == name),
+        }
+    }
+
+    /// Returns the parent of a node, or `None` for sectors and unknown nodes.
+    #[must_use]
+    pub fn parent(&self, level: IndustryLevel, name: &str) -> Option<&str> {
+        match level {
+            IndustryLevel::Industry => self.industries.get(name).map(String::as_str),
+            IndustryLevel::IndustryGroup => self.groups.get(name).map(String::as_str),
+            IndustryLevel::Sector => None,
+        }
+    }
+
+    /// Map an industry (leaf) to its ancestor at the requested level.
+    #[must_use]
+    pub fn ancestor(&self, industry: &str, level: IndustryLevel) -> Option<&str> {
+        let mut current_level = IndustryLevel::Industry;
+        let mut current = self.industries.get_key_value(industry).map(|(k, _)| k.as_str())?;
+        while current_level != level {
+            current = self.parent(current_level, current)?;
+            current_level = current_level.coarser()?;
+        }
+        Some(current)
+    }
+
+    /// Sorted, de-duplicated node names at the given level.
+    #[must_use]
+    pub fn names(&self, level: IndustryLevel) -> Vec<&str> {
+        let mut names: Vec<&str> = match level {
+            IndustryLevel::Industry => self.industries.keys().map(String::as_str).collect(),
+            IndustryLevel::IndustryGroup => self.groups.keys().map(String::as_str).collect(),
+            IndustryLevel::Sector => self.groups.values().map(String::as_str).collect(),
+        };
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Resolve the node each classification at `level` is estimated on.
+    ///
+    /// Any node populated by fewer than `min_names` assets is merged into its
+    /// parent, repeatedly, until every resulting node meets the minimum or has
+    /// reached the sector level. Nodes that meet the minimum are left untouched,
+    /// so a thin industry is rolled up on its own rather than absorbing its
+    /// siblings.
+    ///
+    /// # Arguments
+    /// * `level` - Level the names in `counts` belong to
+    /// * `counts` - Number of assets per node on a single date
+    /// * `min_names` - Minimum number of assets per estimated node
+    ///
+    /// # Returns
+    /// Map from each node in `counts` to its effective `(level, name)`.
+    #[must_use]
+    pub fn roll_up(
+        &self,
+        level: IndustryLevel,
+        counts: &HashMap<String, usize>,
+        min_names: usize,
+    ) -> BTreeMap<String, (IndustryLevel, String)> {
+        let mut assignment: BTreeMap<String, (IndustryLevel, String)> =
+            counts.keys().map(|name| (name.clone(), (level, name.clone()))).collect();
+
+        loop {
+            let mut populations: HashMap<&(IndustryLevel, String), usize> = HashMap::new();
+            for (name, node) in &assignment {
+                *populations.entry(node).or_default() += counts[name];
+            }
+
+            let thin: Vec<(IndustryLevel, String)> = populations
+                .into_iter()
+                .filter(|((node_level, name), n)| {
+                    *n < min_names && self.parent(*node_level, name).is_some()
+                })
+                .map(|(node, _)| node.clone())
+                .collect();
+
+            if thin.is_empty() {
+                return assignment;
+            }
+
+            for node in assignment.values_mut() {
+                if thin.contains(node) {
+                    let (node_level, name) = &*node;
+                    if let (Some(parent_level), Some(parent)) =
+                        (node_level.coarser(), self.parent(*node_level, name))
+                    {
+                        *node = (parent_level, parent.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hierarchy() -> IndustryHierarchy {
+        IndustryHierarchy::from_triples([
+            ("Technology", "Software", "Application Software"),
+            ("Technology", "Software", "Systems Software"),
+            ("Technology", "Hardware", "Semiconductors"),
+            ("Energy", "Energy", "Oil & Gas"),
+        ])
+    }
+
+    #[test]
+    fn ancestor_lookup() {
+        let h = hierarchy();
+        assert_eq!(h.ancestor("Semiconductors", IndustryLevel::IndustryGroup), Some("Hardware"));
+        assert_eq!(h.ancestor("Semiconductors", IndustryLevel::Sector), Some("Technology"));
+        assert_eq!(h.ancestor("Oil & Gas", IndustryLevel::Industry), Some("Oil & Gas"));
+        assert_eq!(h.ancestor("Unknown", IndustryLevel::Sector), None);
+        assert_eq!(h.names(IndustryLevel::Sector), vec!["Energy", "Technology"]);
+    }
+
+    #[test]
+    fn roll_up_thin_industries() {
+        let h = hierarchy();
+        let counts: HashMap<String, usize> = [
+            ("Application Software".to_string(), 10),
+            ("Systems Software".to_string(), 2),
+            ("Semiconductors".to_string(), 1),
+            ("Oil & Gas".to_string(), 8),
+        ]
+        .into_iter()
+        .collect();
+
+        let mapping = h.roll_up(IndustryLevel::Industry, &counts, 5);
+
+        assert_eq!(
+            mapping["Application Software"],
+            (IndustryLevel::Industry, "Application Software".to_string())
+        );
+        // Two names roll up to the group, which is still thin, then to the sector.
+        assert_eq!(mapping["Systems Software"], (IndustryLevel::Sector, "Technology".to_string()));
+        assert_eq!(mapping["Semiconductors"], (IndustryLevel::Sector, "Technology".to_string()));
+        assert_eq!(mapping["Oil & Gas"], (IndustryLevel::Industry, "Oil & Gas".to_string()));
+    }
+
+    #[test]
+    fn roll_up_stops_at_sector() {
+        let h = hierarchy();
+        let counts: HashMap<String, usize> = [("Oil & Gas".to_string(), 1)].into_iter().collect();
+
+        let mapping = h.roll_up(IndustryLevel::Industry, &counts, 5);
+        assert_eq!(mapping["Oil & Gas"], (IndustryLevel::Sector, "Energy".to_string()));
+    }
+}
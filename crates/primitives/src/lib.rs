@@ -6,6 +6,12 @@
 mod asset;
 pub use asset::{Asset, AssetId, Symbol};
 
+mod calendar;
+pub use calendar::{Calendar, CalendarError, CalendarPeriod};
+
+mod classification;
+pub use classification::{IndustryHierarchy, IndustryLevel};
+
 mod factor;
 pub use factor::{FactorExposures, FactorName, FactorReturns};
 
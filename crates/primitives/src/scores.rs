@@ -1,9 +1,11 @@
 //! Factor score type definitions.
 
-use ndarray::{Array1, Array2};
+use std::collections::HashMap;
+
+use ndarray::{Array1, Array2, Axis};
 use serde::{Deserialize, Serialize};
 
-use crate::Date;
+use crate::{Date, IndustryHierarchy, IndustryLevel};
 
 /// Cross-sectional factor scores for a single date.
 #[derive(Debug, Clone)]
@@ -77,6 +79,75 @@ impl SectorScores {
     pub fn sector_index(&self, name: &str) -> Option<usize> {
         self.sector_names.iter().position(|n| n == name)
     }
+
+    /// Sum of sector exposures for each asset (n_assets,).
+    ///
+    /// Valid memberships sum to one, whether one-hot or fractional.
+    #[must_use]
+    pub fn row_sums(&self) -> Array1<f64> {
+        self.exposures.sum_axis(Axis(1))
+    }
+
+    /// Check whether any asset is split across several sectors.
+    #[must_use]
+    pub fn is_fractional(&self) -> bool {
+        self.exposures.iter().any(|&x| x != 0.0 && x != 1.0)
+    }
+
+    /// Roll thinly populated sectors up the classification hierarchy.
+    ///
+    /// Columns are interpreted as nodes at `level` of `hierarchy`. Any node with
+    /// fewer than `min_names` exposed assets is merged into its parent (see
+    /// [`IndustryHierarchy::roll_up`]) and the exposures of merged columns are
+    /// summed. Nodes are kept apart by level as well as name, and named
+    /// `{level}_{name}` (e.g. `industry_group_Energy`), like the `sector_*`
+    /// columns built from a hierarchy. Columns unknown to the hierarchy are
+    /// kept as-is.
+    #[must_use]
+    pub fn roll_up(
+        &self,
+        hierarchy: &IndustryHierarchy,
+        level: IndustryLevel,
+        min_names: usize,
+    ) -> Self {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for (j, name) in self.sector_names.iter().enumerate() {
+            if hierarchy.contains(level, name) {
+                *counts.entry(name.clone()).or_default() +=
+                    self.exposures.column(j).iter().filter(|&&x| x != 0.0).count();
+            }
+        }
+        let mapping = hierarchy.roll_up(level, &counts, min_names);
+
+        // Unknown columns have no level
+        let mut nodes: Vec<(Option<IndustryLevel>, &str)> = Vec::new();
+        let mut targets: Vec<usize> = Vec::with_capacity(self.n_sectors());
+        for name in &self.sector_names {
+            let node = match mapping.get(name) {
+                Some((node_level, node_name)) => (Some(*node_level), node_name.as_str()),
+                None => (None, name.as_str()),
+            };
+            let idx = nodes.iter().position(|n| *n == node).unwrap_or_else(|| {
+                nodes.push(node);
+                nodes.len() - 1
+            });
+            targets.push(idx);
+        }
+
+        let mut exposures = Array2::zeros((self.n_assets(), nodes.len()));
+        for (j, &target) in targets.iter().enumerate() {
+            let mut dst = exposures.column_mut(target);
+            dst += &self.exposures.column(j);
+        }
+
+        let names = nodes
+            .into_iter()
+            .map(|(node_level, name)| {
+                node_level.map_or_else(|| name.to_string(), |l| format!("{l}_{name}"))
+            })
+            .collect();
+        Self::new(names, exposures)
+    }
 }
 
 /// Style factor scores (n_assets x n_styles).
@@ -214,6 +285,57 @@ mod tests {
         assert_eq!(sectors.sector_index("Tech"), Some(0));
     }
 
+    #[test]
+    fn sector_scores_fractional() {
+        let exposures = Array2::from_shape_vec((2, 2), vec![0.7, 0.3, 1.0, 0.0]).unwrap();
+        let sectors = SectorScores::new(vec!["Tech".to_string(), "Finance".to_string()], exposures);
+
+        assert!(sectors.is_fractional());
+        assert_eq!(sectors.row_sums(), array![1.0, 1.0]);
+    }
+
+    #[test]
+    fn sector_scores_roll_up() {
+        let hierarchy = IndustryHierarchy::from_triples([
+            ("Tech", "Software", "Apps"),
+            ("Tech", "Software", "Systems"),
+            ("Tech", "Hardware", "Chips"),
+        ]);
+        // Apps has three names, Systems and Chips one each.
+        let exposures = Array2::from_shape_vec(
+            (5, 3),
+            vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        )
+        .unwrap();
+        let sectors = SectorScores::new(
+            vec!["Apps".to_string(), "Systems".to_string(), "Chips".to_string()],
+            exposures,
+        );
+
+        let rolled = sectors.roll_up(&hierarchy, IndustryLevel::Industry, 2);
+
+        assert_eq!(rolled.sector_names, vec!["industry_Apps", "sector_Tech"]);
+        assert_eq!(rolled.n_assets(), 5);
+        assert_eq!(rolled.exposures.column(1).sum(), 2.0);
+    }
+
+    #[test]
+    fn sector_scores_roll_up_keeps_levels_apart() {
+        // The industry "Tech" shares its name with the sector Apps rolls up to.
+        let hierarchy = IndustryHierarchy::from_triples([
+            ("Tech", "Software", "Apps"),
+            ("Other", "Misc", "Tech"),
+        ]);
+        let exposures = Array2::from_shape_vec((3, 2), vec![1.0, 0.0, 0.0, 1.0, 0.0, 1.0]).unwrap();
+        let sectors = SectorScores::new(vec!["Apps".to_string(), "Tech".to_string()], exposures);
+
+        let rolled = sectors.roll_up(&hierarchy, IndustryLevel::Industry, 2);
+
+        assert_eq!(rolled.sector_names, vec!["sector_Tech", "industry_Tech"]);
+        assert_eq!(rolled.exposures.column(0).sum(), 1.0);
+        assert_eq!(rolled.exposures.column(1).sum(), 2.0);
+    }
+
     #[test]
     fn style_scores_dimensions() {
         let scores = Array2::from_shape_vec((2, 3), vec![0.1, 0.2, 0.3, -0.1, -0.2, -0.3]).unwrap();
@@ -0,0 +1,162 @@
+//! Market beta and residual volatility descriptors.
+
+use polars::prelude::*;
+
+use crate::{HistoricalBetaConfig, StylesError, historical_beta, standardize_scores};
+
+/// Configuration for the beta and residual volatility descriptors.
+#[derive(Debug, Clone)]
+pub struct BetaConfig {
+    /// Exponentially weighted market regression (see [`historical_beta`]).
+    pub regression: HistoricalBetaConfig,
+    /// Cross-sectional winsorization percentile (None to disable).
+    pub winsor_factor: Option<f64>,
+}
+
+impl Default for BetaConfig {
+    fn default() -> Self {
+        Self { regression: HistoricalBetaConfig::default(), winsor_factor: Some(0.01) }
+    }
+}
+
+/// Standardize one output column of [`historical_beta`] into a style score.
+fn regression_score(
+    returns_df: LazyFrame,
+    market_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    config: &BetaConfig,
+    column: &str,
+    score: &str,
+) -> Result<LazyFrame, StylesError> {
+    let df = historical_beta(returns_df, market_df, None, &config.regression)?.lazy().select([
+        col("date"),
+        col("symbol"),
+        col(column).alias(score),
+    ]);
+    Ok(standardize_scores(df, score, mkt_cap_df, config.winsor_factor))
+}
+
+/// Compute market beta scores.
+///
+/// Beta is the slope of the exponentially weighted [`historical_beta`]
+/// regression of each asset's returns on the market return.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `market_df` - LazyFrame with | date | market_return |, e.g. from
+///   [`market_returns`](crate::market_returns)
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the mean
+/// * `config` - Regression and winsorization settings
+///
+/// # Returns
+/// LazyFrame with | date | symbol | beta_score |, standardized per date (see
+/// [`standardize_scores`]).
+///
+/// # Errors
+/// Returns `StylesError` if the regression settings are invalid or the inputs
+/// are missing columns.
+pub fn beta(
+    returns_df: LazyFrame,
+    market_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    config: &BetaConfig,
+) -> Result<LazyFrame, StylesError> {
+    regression_score(returns_df, market_df, mkt_cap_df, config, "beta", "beta_score")
+}
+
+/// Compute residual volatility scores.
+///
+/// Residual volatility is the weighted standard deviation of the residuals of
+/// the same [`historical_beta`] regression used by [`beta`].
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `market_df` - LazyFrame with | date | market_return |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the mean
+/// * `config` - Regression and winsorization settings
+///
+/// # Returns
+/// LazyFrame with | date | symbol | resvol_score |, standardized per date (see
+/// [`standardize_scores`]).
+///
+/// # Errors
+/// Returns `StylesError` if the regression settings are invalid or the inputs
+/// are missing columns.
+pub fn residual_volatility(
+    returns_df: LazyFrame,
+    market_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    config: &BetaConfig,
+) -> Result<LazyFrame, StylesError> {
+    regression_score(returns_df, market_df, mkt_cap_df, config, "resvol", "resvol_score")
+}
+
+#[cfg(test)]
+mod tests {
+    use toraniko_primitives::ExponentialWeights;
+
+    use super::*;
+
+    fn frames() -> (LazyFrame, LazyFrame) {
+        let market = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02];
+        let noise = [0.001, -0.002, 0.0, 0.002, -0.001, 0.0];
+        let mut dates = Vec::new();
+        let mut symbols = Vec::new();
+        let mut returns = Vec::new();
+        for (t, m) in market.iter().enumerate() {
+            for (symbol, b, e) in [("A", 0.5, 0.0), ("B", 1.0, noise[t]), ("C", 1.5, 0.0)] {
+                dates.push(t as i32);
+                symbols.push(symbol);
+                returns.push(b * m + e);
+            }
+        }
+        let returns_df = df! {
+            "date" => &dates, "symbol" => &symbols, "asset_returns" => &returns,
+        }
+        .unwrap()
+        .lazy();
+        let market_df = df! {
+            "date" => (0..market.len() as i32).collect::<Vec<_>>(),
+            "market_return" => &market,
+        }
+        .unwrap()
+        .lazy();
+        (returns_df, market_df)
+    }
+
+    #[test]
+    fn beta_and_resvol_scores() {
+        let (returns_df, market_df) = frames();
+        let caps =
+            returns_df.clone().select([col("date"), col("symbol"), lit(1e9).alias("market_cap")]);
+        let config = BetaConfig {
+            regression: HistoricalBetaConfig {
+                weights: ExponentialWeights::new(6, 3),
+                min_periods: 4,
+            },
+            winsor_factor: None,
+        };
+
+        let betas = beta(returns_df.clone(), market_df.clone(), caps.clone(), &config)
+            .unwrap()
+            .filter(col("date").eq(lit(5)))
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let scores: Vec<f64> =
+            betas.column("beta_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+        assert!(scores[0] < scores[1] && scores[1] < scores[2]);
+
+        let resvol = residual_volatility(returns_df, market_df, caps, &config)
+            .unwrap()
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        // Dates 3, 4 and 5 have at least four observations for all three symbols
+        assert_eq!(resvol.height(), 9);
+        // Only B has idiosyncratic noise
+        let scores: Vec<f64> =
+            resvol.column("resvol_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+        assert!(scores[1] > scores[0] && scores[1] > scores[2]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Composite style factors built from weighted descriptors.
+
+use polars::prelude::*;
+use toraniko_math::{center_xsection, weighted_center_xsection, winsorize_xsection};
+
+/// A style factor defined as a weighted combination of descriptors.
+///
+/// Each descriptor is standardized per date by subtracting its cap-weighted
+/// mean and dividing by its equal-weighted standard deviation, so the
+/// composite is neutral for the cap-weighted market. When an asset is missing
+/// some descriptors, the weights of the available ones are renormalized to sum
+/// to one. The composite is then re-standardized the same way.
+///
+/// ```rust,ignore
+/// let val = CompositeFactor::new("val_score")
+///     .with_descriptor("book_price", 0.5)
+///     .with_descriptor("earnings_price", 0.3)
+///     .with_descriptor("sales_price", 0.2)
+///     .with_winsorization(0.01)
+///     .build(descriptors_df);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeFactor {
+    score: String,
+    descriptors: Vec<(String, f64)>,
+    weight_col: Option<String>,
+    winsor_factor: Option<f64>,
+}
+
+impl CompositeFactor {
+    /// Create an empty composite producing the `score` column.
+    ///
+    /// The estimator picks up style columns ending in `_score`.
+    #[must_use]
+    pub fn new(score: impl Into<String>) -> Self {
+        Self {
+            score: score.into(),
+            descriptors: Vec::new(),
+            weight_col: Some("market_cap".to_string()),
+            winsor_factor: None,
+        }
+    }
+
+    /// Add a descriptor column with its weight in the composite.
+    #[must_use]
+    pub fn with_descriptor(mut self, column: impl Into<String>, weight: f64) -> Self {
+        self.descriptors.push((column.into(), weight));
+        self
+    }
+
+    /// Set the column used for the weighted mean (None for the simple mean).
+    ///
+    /// Defaults to `market_cap`.
+    #[must_use]
+    pub fn with_weight_col(mut self, column: Option<&str>) -> Self {
+        self.weight_col = column.map(str::to_string);
+        self
+    }
+
+    /// Winsorize each descriptor per date at the given percentile before standardizing.
+    #[must_use]
+    pub const fn with_winsorization(mut self, percentile: f64) -> Self {
+        self.winsor_factor = Some(percentile);
+        self
+    }
+
+    /// Name of the output score column.
+    #[must_use]
+    pub fn score(&self) -> &str {
+        &self.score
+    }
+
+    /// Descriptor columns and their weights.
+    #[must_use]
+    pub fn descriptors(&self) -> &[(String, f64)] {
+        &self.descriptors
+    }
+
+    /// Standardize `column` per date: subtract the (weighted) mean, divide by the std.
+    fn standardize(&self, column: &str) -> Expr {
+        self.weight_col.as_deref().map_or_else(
+            || center_xsection(column, "date", true),
+            |w| weighted_center_xsection(column, w, "date", true),
+        )
+    }
+
+    /// Compute the composite score.
+    ///
+    /// # Arguments
+    /// * `df` - LazyFrame with | date | symbol | descriptors... | and the weight
+    ///   column (`market_cap` by default)
+    ///
+    /// # Returns
+    /// LazyFrame with | date | symbol | score |. Assets with none of the
+    /// descriptors on a date are dropped.
+    pub fn build(&self, df: LazyFrame) -> LazyFrame {
+        let columns: Vec<&str> = self.descriptors.iter().map(|(c, _)| c.as_str()).collect();
+
+        let mut df = df;
+        for &c in &columns {
+            df = df.with_column(col(c).cast(DataType::Float64).alias(c));
+        }
+        if let Some(p) = self.winsor_factor {
+            df = winsorize_xsection(df, &columns, "date", p);
+        }
+        let df = df.with_columns(
+            columns.iter().map(|&c| self.standardize(c).alias(c)).collect::<Vec<_>>(),
+        );
+
+        let weighted_sum = self
+            .descriptors
+            .iter()
+            .map(|(c, w)| col(c.as_str()).fill_null(lit(0.0)) * lit(*w))
+            .reduce(|a, b| a + b)
+            .unwrap_or_else(|| lit(0.0));
+        let available_weight = self
+            .descriptors
+            .iter()
+            .map(|(c, w)| col(c.as_str()).is_not_null().cast(DataType::Float64) * lit(*w))
+            .reduce(|a, b| a + b)
+            .unwrap_or_else(|| lit(0.0));
+
+        let score = self.score.as_str();
+        let mut keep =
+            vec![col("date"), col("symbol"), (weighted_sum / available_weight).alias(score)];
+        if let Some(w) = &self.weight_col {
+            keep.push(col(w.as_str()));
+        }
+
+        let df = df.select(keep).filter(col(score).is_finite());
+        df.with_column(self.standardize(score).alias(score)).select([
+            col("date"),
+            col("symbol"),
+            col(score),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn frame() -> LazyFrame {
+        df! {
+            "date" => &[1, 1, 1, 1],
+            "symbol" => &["A", "B", "C", "D"],
+            "market_cap" => &[4.0, 3.0, 2.0, 1.0],
+            "book_price" => &[Some(0.2), Some(0.4), Some(0.6), None],
+            "earnings_price" => &[Some(0.05), Some(0.01), Some(0.08), Some(0.1)],
+        }
+        .unwrap()
+        .lazy()
+    }
+
+    #[test]
+    fn composite_is_cap_neutral() {
+        let result = CompositeFactor::new("val_score")
+            .with_descriptor("book_price", 0.6)
+            .with_descriptor("earnings_price", 0.4)
+            .build(frame())
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+
+        let scores: Vec<f64> =
+            result.column("val_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+        assert_eq!(scores.len(), 4);
+
+        let caps = [4.0, 3.0, 2.0, 1.0];
+        let cap_mean: f64 = scores.iter().zip(caps).map(|(s, c)| s * c).sum::<f64>() / 10.0;
+        assert_relative_eq!(cap_mean, 0.0, epsilon = 1e-12);
+
+        let mean = scores.iter().sum::<f64>() / 4.0;
+        let var = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / 3.0;
+        assert_relative_eq!(var.sqrt(), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn missing_descriptors_renormalize_weights() {
+        let result = CompositeFactor::new("val_score")
+            .with_descriptor("book_price", 0.6)
+            .with_descriptor("earnings_price", 0.4)
+            .with_weight_col(None)
+            .build(frame())
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let scores: Vec<f64> =
+            result.column("val_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+
+        let zscore = |x: &[f64]| {
+            let n = x.len() as f64;
+            let mean = x.iter().sum::<f64>() / n;
+            let std = (x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            x.iter().map(|v| (v - mean) / std).collect::<Vec<_>>()
+        };
+        let book = zscore(&[0.2, 0.4, 0.6]);
+        let earnings = zscore(&[0.05, 0.01, 0.08, 0.1]);
+        // D has no book yield, so its composite is its earnings z-score alone
+        let raw: Vec<f64> = (0..3)
+            .map(|i| 0.6 * book[i] + 0.4 * earnings[i])
+            .chain(std::iter::once(earnings[3]))
+            .collect();
+        let expected = zscore(&raw);
+
+        for (s, e) in scores.iter().zip(&expected) {
+            assert_relative_eq!(*s, *e, epsilon = 1e-12);
+        }
+    }
+}
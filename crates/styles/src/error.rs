@@ -0,0 +1,28 @@
+//! Error types for style descriptors.
+
+/// Errors that can occur while computing style descriptors.
+#[derive(Debug, thiserror::Error)]
+pub enum StylesError {
+    /// Polars error.
+    #[error("polars error: {0}")]
+    Polars(#[from] polars::error::PolarsError),
+
+    /// Math error.
+    #[error("math error: {0}")]
+    Math(#[from] toraniko_math::MathError),
+
+    /// Invalid parameter.
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_display() {
+        let err = StylesError::InvalidParameter("window must be positive".to_string());
+        assert!(err.to_string().contains("window must be positive"));
+    }
+}
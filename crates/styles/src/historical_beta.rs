@@ -0,0 +1,225 @@
+//! Exponentially weighted historical beta.
+
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use toraniko_math::weighted_least_squares;
+use toraniko_primitives::ExponentialWeights;
+
+use crate::StylesError;
+
+/// Configuration for [`historical_beta`].
+#[derive(Debug, Clone)]
+pub struct HistoricalBetaConfig {
+    /// Trailing window and half-life of the regression weights.
+    pub weights: ExponentialWeights,
+    /// Minimum number of observations in the window required for an estimate.
+    pub min_periods: usize,
+}
+
+impl Default for HistoricalBetaConfig {
+    fn default() -> Self {
+        Self { weights: ExponentialWeights::new(252, 63), min_periods: 126 }
+    }
+}
+
+/// Compute cap-weighted market returns.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+///
+/// # Returns
+/// LazyFrame with | date | market_return |, sorted by date.
+pub fn market_returns(returns_df: LazyFrame, mkt_cap_df: LazyFrame) -> LazyFrame {
+    returns_df
+        .join(
+            mkt_cap_df,
+            [col("date"), col("symbol")],
+            [col("date"), col("symbol")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .filter(col("asset_returns").is_not_null().and(col("market_cap").is_not_null()))
+        .group_by([col("date")])
+        .agg([((col("asset_returns") * col("market_cap")).sum() / col("market_cap").sum())
+            .alias("market_return")])
+        .sort(["date"], SortMultipleOptions::default())
+}
+
+/// Estimate historical betas by exponentially weighted time-series regression.
+///
+/// For every date and symbol, the asset's excess returns over the trailing
+/// window are regressed on excess market returns with weights decaying by
+/// the configured half-life (most recent observation weighted highest).
+/// Dates with fewer than `min_periods` observations, or a window in which the
+/// market return is constant, produce no row.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `market_df` - LazyFrame with | date | market_return |, e.g. from [`market_returns`]
+/// * `risk_free_df` - Optional LazyFrame with | date | risk_free |, subtracted from
+///   both asset and market returns
+/// * `config` - Regression weights and minimum observations
+///
+/// # Returns
+/// DataFrame with | date | symbol | beta | alpha | resvol |, where `resvol` is
+/// the weighted standard deviation of the regression residuals.
+///
+/// # Errors
+/// Returns `StylesError::InvalidParameter` if the weights are empty, or
+/// `StylesError::Polars` if the inputs are missing columns.
+pub fn historical_beta(
+    returns_df: LazyFrame,
+    market_df: LazyFrame,
+    risk_free_df: Option<LazyFrame>,
+    config: &HistoricalBetaConfig,
+) -> Result<DataFrame, StylesError> {
+    let window = config.weights.window();
+    if window == 0 || config.weights.half_life() == 0 {
+        return Err(StylesError::InvalidParameter(
+            "historical beta needs a positive window and half-life".to_string(),
+        ));
+    }
+    let min_periods = config.min_periods.clamp(2, window);
+
+    let joined =
+        returns_df.join(market_df, [col("date")], [col("date")], JoinArgs::new(JoinType::Inner));
+    let joined = match risk_free_df {
+        Some(rf) => joined
+            .join(rf, [col("date")], [col("date")], JoinArgs::new(JoinType::Left))
+            .with_columns([
+                (col("asset_returns") - col("risk_free").fill_null(lit(0.0)))
+                    .alias("asset_returns"),
+                (col("market_return") - col("risk_free").fill_null(lit(0.0)))
+                    .alias("market_return"),
+            ]),
+        None => joined,
+    };
+    let df = joined
+        .filter(col("asset_returns").is_not_null().and(col("market_return").is_not_null()))
+        .select([
+            col("date"),
+            col("symbol"),
+            col("asset_returns").cast(DataType::Float64),
+            col("market_return").cast(DataType::Float64),
+        ])
+        .sort(["symbol", "date"], SortMultipleOptions::default())
+        .collect()?;
+
+    let symbols = df.column("symbol")?.str()?;
+    let asset = df.column("asset_returns")?.f64()?;
+    let market = df.column("market_return")?.f64()?;
+    let weights = config.weights.weights();
+
+    let mut rows: Vec<u32> = Vec::new();
+    let (mut betas, mut alphas, mut resvols) = (Vec::new(), Vec::new(), Vec::new());
+
+    let mut start = 0;
+    while start < df.height() {
+        let symbol = symbols.get(start);
+        let end = (start..df.height()).find(|&i| symbols.get(i) != symbol).unwrap_or(df.height());
+
+        for t in start..end {
+            let first = (t + 1).saturating_sub(window).max(start);
+            let n = t + 1 - first;
+            if n < min_periods {
+                continue;
+            }
+
+            // Row k is lag t - first - k; the most recent observation has lag 0
+            let y: Array1<f64> = (first..=t).map(|i| asset.get(i).unwrap_or(0.0)).collect();
+            let mut x = Array2::ones((n, 2));
+            let mut w = Array1::zeros(n);
+            for (k, i) in (first..=t).enumerate() {
+                x[[k, 1]] = market.get(i).unwrap_or(0.0);
+                w[k] = weights[t - i];
+            }
+
+            let Ok(fit) = weighted_least_squares(&y, &x, &w.mapv(f64::sqrt)) else { continue };
+            if !fit.coefficients.iter().all(|c| c.is_finite()) {
+                continue;
+            }
+            let variance = (&w * &fit.residuals.mapv(|e| e * e)).sum() / w.sum();
+
+            rows.push(t as u32);
+            alphas.push(fit.coefficients[0]);
+            betas.push(fit.coefficients[1]);
+            resvols.push(variance.sqrt());
+        }
+        start = end;
+    }
+
+    let idx = IdxCa::from_vec("idx".into(), rows);
+    let mut out = df.select(["date", "symbol"])?.take(&idx)?;
+    out.with_column(Column::new("beta".into(), betas))?;
+    out.with_column(Column::new("alpha".into(), alphas))?;
+    out.with_column(Column::new("resvol".into(), resvols))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn recovers_beta_alpha_and_resvol() {
+        let market = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02, -0.005, 0.012];
+        let mut dates = Vec::new();
+        let mut symbols = Vec::new();
+        let mut returns = Vec::new();
+        for (t, m) in market.iter().enumerate() {
+            for (symbol, b) in [("A", 0.8), ("B", 1.3)] {
+                dates.push(t as i32);
+                symbols.push(symbol);
+                returns.push(0.001 + b * m);
+            }
+        }
+        let returns_df = df! {
+            "date" => &dates, "symbol" => &symbols, "asset_returns" => &returns,
+        }
+        .unwrap()
+        .lazy();
+        let market_df = df! {
+            "date" => (0..market.len() as i32).collect::<Vec<_>>(),
+            "market_return" => &market,
+        }
+        .unwrap()
+        .lazy();
+
+        let config =
+            HistoricalBetaConfig { weights: ExponentialWeights::new(5, 2), min_periods: 4 };
+        let result = historical_beta(returns_df, market_df, None, &config).unwrap();
+
+        // Dates 3..=7 for each of the two symbols
+        assert_eq!(result.height(), 10);
+        let symbols = result.column("symbol").unwrap().str().unwrap();
+        let betas = result.column("beta").unwrap().f64().unwrap();
+        let alphas = result.column("alpha").unwrap().f64().unwrap();
+        let resvols = result.column("resvol").unwrap().f64().unwrap();
+        for i in 0..result.height() {
+            let expected = if symbols.get(i) == Some("A") { 0.8 } else { 1.3 };
+            assert_relative_eq!(betas.get(i).unwrap(), expected, epsilon = 1e-8);
+            assert_relative_eq!(alphas.get(i).unwrap(), 0.001, epsilon = 1e-8);
+            assert!(resvols.get(i).unwrap() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn cap_weighted_market() {
+        let returns_df = df! {
+            "date" => &[1, 1], "symbol" => &["A", "B"], "asset_returns" => &[0.01, 0.04],
+        }
+        .unwrap()
+        .lazy();
+        let mkt_cap_df = df! {
+            "date" => &[1, 1], "symbol" => &["A", "B"], "market_cap" => &[3.0, 1.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = market_returns(returns_df, mkt_cap_df).collect().unwrap();
+        let value = result.column("market_return").unwrap().f64().unwrap().get(0).unwrap();
+        assert_relative_eq!(value, 0.0175, epsilon = 1e-12);
+    }
+}
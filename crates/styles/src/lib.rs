@@ -0,0 +1,37 @@
+#![doc = include_str!("../README.md")]
+#![doc(issue_tracker_base_url = "https://github.com/factordynamics/toraniko-rs/issues/")]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+mod standardize;
+pub use standardize::standardize_scores;
+
+mod composite;
+pub use composite::CompositeFactor;
+
+mod momentum;
+pub use momentum::{MomentumConfig, momentum};
+
+mod size;
+pub use size::size;
+
+mod nonlinear_size;
+pub use nonlinear_size::nonlinear_size;
+
+mod value;
+pub use value::value;
+
+mod beta;
+pub use beta::{BetaConfig, beta, residual_volatility};
+
+mod historical_beta;
+pub use historical_beta::{HistoricalBetaConfig, historical_beta, market_returns};
+
+mod liquidity;
+pub use liquidity::liquidity;
+
+mod reversal;
+pub use reversal::short_term_reversal;
+
+mod error;
+pub use error::StylesError;
@@ -0,0 +1,69 @@
+//! Liquidity descriptor.
+
+use polars::prelude::*;
+
+use crate::standardize_scores;
+
+/// Compute liquidity scores from share turnover.
+///
+/// Liquidity is the log of the average daily turnover (volume divided by
+/// shares outstanding) over a trailing window. Assets without a full window
+/// or with zero turnover are dropped.
+///
+/// # Arguments
+/// * `turnover_df` - LazyFrame with | date | symbol | turnover |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the mean
+/// * `window` - Number of trailing periods (e.g. 21 for monthly turnover)
+///
+/// # Returns
+/// LazyFrame with | date | symbol | liq_score |, standardized per date (see
+/// [`standardize_scores`]).
+pub fn liquidity(turnover_df: LazyFrame, mkt_cap_df: LazyFrame, window: usize) -> LazyFrame {
+    let window = window.max(1);
+    let options = RollingOptionsFixedWindow {
+        window_size: window,
+        min_periods: window,
+        ..Default::default()
+    };
+
+    let df = turnover_df
+        .sort(["date"], SortMultipleOptions::new().with_maintain_order(true))
+        .with_column(
+            col("turnover")
+                .cast(DataType::Float64)
+                .rolling_mean(options)
+                .over([col("symbol")])
+                .log(std::f64::consts::E)
+                .alias("liq_score"),
+        )
+        .select([col("date"), col("symbol"), col("liq_score")]);
+
+    standardize_scores(df, "liq_score", mkt_cap_df, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn liquidity_ranks_turnover() {
+        let df = df! {
+            "date" => &[1, 2, 1, 2, 1, 2],
+            "symbol" => &["A", "A", "B", "B", "C", "C"],
+            "turnover" => &[0.001, 0.003, 0.01, 0.01, 0.0, 0.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let caps = df.clone().select([col("date"), col("symbol"), lit(1e9).alias("market_cap")]);
+        let result = liquidity(df, caps, 2).collect().unwrap();
+        let symbols: Vec<&str> =
+            result.column("symbol").unwrap().str().unwrap().into_no_null_iter().collect();
+        let scores: Vec<f64> =
+            result.column("liq_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+
+        // C never trades and is dropped; B trades more than A
+        assert_eq!(symbols, vec!["A", "B"]);
+        assert!(scores[1] > scores[0]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Momentum descriptor.
+
+use polars::prelude::*;
+use toraniko_math::exp_weights;
+
+use crate::standardize_scores;
+
+/// Configuration for the momentum descriptor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MomentumConfig {
+    /// Number of trailing periods in the formation window.
+    pub window: usize,
+    /// Most recent periods skipped to avoid short-term reversal (e.g. one month).
+    pub skip: usize,
+    /// Half-life in periods for exponential weighting (None for equal weights).
+    pub half_life: Option<usize>,
+    /// Cross-sectional winsorization percentile (None to disable).
+    pub winsor_factor: Option<f64>,
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self { window: 252, skip: 21, half_life: Some(126), winsor_factor: Some(0.01) }
+    }
+}
+
+/// Compute momentum scores.
+///
+/// Momentum is the (optionally exponentially weighted) sum of log returns over
+/// `window` periods ending `skip` periods before each date. Null returns count
+/// as missing, so assets without a full window of non-null returns are dropped.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the mean
+/// * `config` - Window, skip, weighting and winsorization settings
+///
+/// # Returns
+/// LazyFrame with | date | symbol | mom_score |, standardized per date (see
+/// [`standardize_scores`]).
+pub fn momentum(
+    returns_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    config: &MomentumConfig,
+) -> LazyFrame {
+    let window = config.window.max(1);
+    // Weights are applied oldest first; scale so equal weights reduce to a plain sum
+    let weights = config.half_life.map(|half_life| {
+        exp_weights(window, half_life).iter().rev().map(|w| w * window as f64).collect()
+    });
+    let options = |weights: Option<Vec<f64>>| RollingOptionsFixedWindow {
+        window_size: window,
+        min_periods: window,
+        weights,
+        ..Default::default()
+    };
+
+    // Weighted rolling sums reject nulls: sum with nulls as zero and keep only
+    // windows where every return is present
+    let log_returns = col("asset_returns").log1p();
+    let present =
+        log_returns.clone().is_not_null().cast(DataType::Float64).rolling_sum(options(None));
+    let total = log_returns.fill_null(lit(0.0)).rolling_sum(options(weights));
+
+    let df = returns_df
+        .sort(["date"], SortMultipleOptions::new().with_maintain_order(true))
+        .with_column(
+            // Shift the rolling sum rather than the returns, so the skipped
+            // periods never enter the window
+            when(present.eq(lit(window as f64)))
+                .then(total)
+                .otherwise(lit(NULL))
+                .shift(lit(config.skip as i64))
+                .over([col("symbol")])
+                .alias("mom_score"),
+        )
+        .select([col("date"), col("symbol"), col("mom_score")]);
+
+    standardize_scores(df, "mom_score", mkt_cap_df, config.winsor_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_caps(df: &LazyFrame) -> LazyFrame {
+        df.clone().select([col("date"), col("symbol"), lit(1.0).alias("market_cap")])
+    }
+
+    #[test]
+    fn momentum_skips_recent_returns() {
+        // A trends up, B trends down; both reverse sharply in the skipped period
+        let df = df! {
+            "date" => &[1, 2, 3, 4, 1, 2, 3, 4],
+            "symbol" => &["A", "A", "A", "A", "B", "B", "B", "B"],
+            "asset_returns" => &[0.05, 0.05, -0.5, -0.5, -0.05, -0.05, 0.5, 0.5],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = MomentumConfig { window: 2, skip: 2, half_life: None, winsor_factor: None };
+        let result = momentum(df.clone(), unit_caps(&df), &config)
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+
+        // Only date 4 has a full window of skipped-lag returns
+        assert_eq!(result.height(), 2);
+        let scores = result.column("mom_score").unwrap().f64().unwrap();
+        assert!(scores.get(0).unwrap() > 0.0);
+        assert!(scores.get(1).unwrap() < 0.0);
+    }
+
+    fn trending_returns(days: usize) -> LazyFrame {
+        // A drifts up and B down, with alternating noise and one missing return each
+        let mut dates = Vec::new();
+        let mut symbols = Vec::new();
+        let mut returns = Vec::new();
+        for (symbol, drift) in [("A", 0.002), ("B", -0.002)] {
+            for day in 0..days {
+                dates.push(day as i32);
+                symbols.push(symbol);
+                let noise = if day % 2 == 0 { 0.01 } else { -0.01 };
+                returns.push((day != 3).then_some(drift + noise));
+            }
+        }
+        df! { "date" => dates, "symbol" => symbols, "asset_returns" => returns }.unwrap().lazy()
+    }
+
+    #[test]
+    fn momentum_default_config_is_weighted() {
+        let config = MomentumConfig::default();
+        let returns = trending_returns(300);
+        let result = momentum(returns.clone(), unit_caps(&returns), &config)
+            .sort(["date", "symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+
+        // Windows containing the missing return are dropped
+        let first = config.window + config.skip + 3;
+        assert_eq!(result.height(), 2 * (300 - first));
+        let scores = result.column("mom_score").unwrap().f64().unwrap();
+        assert!(scores.get(0).unwrap() > 0.0);
+        assert!(scores.get(1).unwrap() < 0.0);
+    }
+
+    #[test]
+    fn momentum_unweighted_skips_null_returns() {
+        let config = MomentumConfig { window: 10, skip: 2, half_life: None, winsor_factor: None };
+        let returns = trending_returns(30);
+        let result = momentum(returns.clone(), unit_caps(&returns), &config).collect().unwrap();
+        assert_eq!(result.height(), 2 * (30 - (10 + 2 + 3)));
+    }
+}
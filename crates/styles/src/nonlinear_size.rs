@@ -0,0 +1,124 @@
+//! Non-linear size (mid-cap) descriptor.
+
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use toraniko_math::orthogonalize;
+
+use crate::{StylesError, size, standardize_scores};
+
+/// Compute non-linear size scores.
+///
+/// The cube of the [`size`] score is orthogonalized to the size score (with an
+/// intercept) per date by a market-cap-weighted regression; the residual
+/// isolates mid-caps from the linear size effect. The residual is then
+/// winsorized and re-standardized with [`standardize_scores`].
+///
+/// To keep the score orthogonal to size after the sector step of the factor
+/// regression, also add a `StyleOrthogonalization` of `nlsize_score` against
+/// `sze_score` to the estimator configuration.
+///
+/// # Arguments
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+/// * `winsor_factor` - Cross-sectional winsorization percentile (None to disable)
+///
+/// # Returns
+/// LazyFrame with | date | symbol | nlsize_score |.
+///
+/// # Errors
+/// Returns `StylesError` if the inputs cannot be evaluated or a per-date
+/// regression is singular.
+pub fn nonlinear_size(
+    mkt_cap_df: LazyFrame,
+    winsor_factor: Option<f64>,
+) -> Result<LazyFrame, StylesError> {
+    let caps = mkt_cap_df.clone().select([
+        col("date"),
+        col("symbol"),
+        col("market_cap").cast(DataType::Float64),
+    ]);
+    let df = size(mkt_cap_df.clone())
+        .join(
+            caps,
+            [col("date"), col("symbol")],
+            [col("date"), col("symbol")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .sort(["date", "symbol"], SortMultipleOptions::default())
+        .collect()?;
+
+    let dates = df.column("date")?;
+    let sizes = df.column("sze_score")?.f64()?;
+    let weights = df.column("market_cap")?.f64()?;
+
+    let mut scores: Vec<f64> = Vec::with_capacity(df.height());
+    let mut start = 0;
+    while start < df.height() {
+        let date = dates.get(start)?;
+        let mut end = start + 1;
+        while end < df.height() && dates.get(end)? == date {
+            end += 1;
+        }
+
+        let x: Array1<f64> = (start..end).map(|i| sizes.get(i).unwrap_or(0.0)).collect();
+        let w: Array1<f64> = (start..end).map(|i| weights.get(i).unwrap_or(0.0)).collect();
+        let mut regressors = Array2::ones((x.len(), 2));
+        regressors.column_mut(1).assign(&x);
+        scores.extend(orthogonalize(&x.mapv(|v| v.powi(3)), &regressors, &w)?);
+        start = end;
+    }
+
+    let mut out = df.select(["date", "symbol"])?;
+    out.with_column(Column::new("nlsize_score".into(), scores))?;
+    Ok(standardize_scores(out.lazy(), "nlsize_score", mkt_cap_df, winsor_factor))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+    use crate::size;
+
+    #[test]
+    fn nonlinear_size_is_orthogonal_to_size() {
+        let caps = [1e8, 5e8, 1e9, 3e9, 1e10, 4e10, 2e11, 1e12];
+        let df = df! {
+            "date" => vec![1; caps.len()],
+            "symbol" => (0..caps.len()).map(|i| format!("S{i}")).collect::<Vec<_>>(),
+            "market_cap" => &caps,
+        }
+        .unwrap()
+        .lazy();
+
+        let nl = nonlinear_size(df.clone(), None).unwrap();
+        let result = size(df.clone())
+            .join(
+                nl,
+                [col("date"), col("symbol")],
+                [col("date"), col("symbol")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .join(
+                df,
+                [col("date"), col("symbol")],
+                [col("date"), col("symbol")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .collect()
+            .unwrap();
+
+        let get = |name: &str| -> Vec<f64> {
+            result.column(name).unwrap().f64().unwrap().into_no_null_iter().collect()
+        };
+        let (sze, nl, w) = (get("sze_score"), get("nlsize_score"), get("market_cap"));
+        let total: f64 = w.iter().sum();
+
+        let cap_mean: f64 = nl.iter().zip(&w).map(|(x, w)| x * w).sum::<f64>() / total;
+        let sze_mean: f64 = sze.iter().zip(&w).map(|(x, w)| x * w).sum::<f64>() / total;
+        let cov: f64 =
+            nl.iter().zip(&sze).zip(&w).map(|((x, s), w)| w * x * (s - sze_mean)).sum::<f64>();
+
+        assert_relative_eq!(cap_mean, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(cov / total, 0.0, epsilon = 1e-10);
+    }
+}
@@ -0,0 +1,67 @@
+//! Short-term reversal descriptor.
+
+use polars::prelude::*;
+
+use crate::standardize_scores;
+
+/// Compute short-term reversal scores.
+///
+/// Reversal is the negated sum of log returns over the trailing `window`
+/// periods, so recent losers score positively.
+///
+/// # Arguments
+/// * `returns_df` - LazyFrame with | date | symbol | asset_returns |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the mean
+/// * `window` - Number of trailing periods (e.g. 21)
+///
+/// # Returns
+/// LazyFrame with | date | symbol | str_score |, standardized per date (see
+/// [`standardize_scores`]).
+pub fn short_term_reversal(
+    returns_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    window: usize,
+) -> LazyFrame {
+    let window = window.max(1);
+    let options = RollingOptionsFixedWindow {
+        window_size: window,
+        min_periods: window,
+        ..Default::default()
+    };
+
+    let df = returns_df
+        .sort(["date"], SortMultipleOptions::new().with_maintain_order(true))
+        .with_column(
+            (lit(0.0) - col("asset_returns").log1p().rolling_sum(options).over([col("symbol")]))
+                .alias("str_score"),
+        )
+        .select([col("date"), col("symbol"), col("str_score")]);
+
+    standardize_scores(df, "str_score", mkt_cap_df, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_losers_score_higher() {
+        let df = df! {
+            "date" => &[1, 2, 3, 1, 2, 3],
+            "symbol" => &["A", "A", "A", "B", "B", "B"],
+            "asset_returns" => &[0.0, 0.05, 0.04, 0.0, -0.05, -0.03],
+        }
+        .unwrap()
+        .lazy();
+
+        let caps = df.clone().select([col("date"), col("symbol"), lit(1e9).alias("market_cap")]);
+        let result = short_term_reversal(df, caps, 2)
+            .filter(col("date").eq(lit(3)))
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let scores = result.column("str_score").unwrap().f64().unwrap();
+
+        assert!(scores.get(1).unwrap() > scores.get(0).unwrap());
+    }
+}
@@ -0,0 +1,59 @@
+//! Size descriptor.
+
+use polars::prelude::*;
+
+use crate::standardize_scores;
+
+/// Compute size scores as standardized log market capitalization.
+///
+/// Positive scores are large caps; negate the score for a small-minus-big
+/// orientation. Non-positive market caps are dropped.
+///
+/// # Arguments
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |
+///
+/// # Returns
+/// LazyFrame with | date | symbol | sze_score |, standardized per date (see
+/// [`standardize_scores`]).
+pub fn size(mkt_cap_df: LazyFrame) -> LazyFrame {
+    let df = mkt_cap_df.clone().select([
+        col("date"),
+        col("symbol"),
+        col("market_cap").cast(DataType::Float64).log(std::f64::consts::E).alias("sze_score"),
+    ]);
+
+    standardize_scores(df, "sze_score", mkt_cap_df, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn size_is_standardized_log_cap() {
+        let df = df! {
+            "date" => &[1, 1, 1, 1],
+            "symbol" => &["A", "B", "C", "D"],
+            "market_cap" => &[1e9, 1e10, 1e11, 0.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = size(df).collect().unwrap();
+        let scores: Vec<f64> =
+            result.column("sze_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+
+        // Zero cap is dropped; log caps are equally spaced one std apart
+        assert_eq!(scores.len(), 3);
+        assert_relative_eq!(scores[1] - scores[0], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(scores[2] - scores[1], 1.0, epsilon = 1e-12);
+
+        // Cap-weighted mean is zero
+        let caps = [1e9, 1e10, 1e11];
+        let cap_mean: f64 =
+            scores.iter().zip(caps).map(|(s, c)| s * c).sum::<f64>() / caps.iter().sum::<f64>();
+        assert_relative_eq!(cap_mean, 0.0, epsilon = 1e-12);
+    }
+}
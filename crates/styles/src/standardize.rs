@@ -0,0 +1,52 @@
+//! Cross-sectional score standardization.
+
+use polars::prelude::*;
+use toraniko_math::{weighted_center_xsection, winsorize_xsection};
+
+/// Temporary column holding the market cap weights.
+const WEIGHT_COLUMN: &str = "_cap_weight";
+
+/// Standardize a raw score column cross-sectionally.
+///
+/// Drops rows without a finite score or a market cap, winsorizes per date (if
+/// `winsor_factor` is set) and standardizes per date by subtracting the
+/// cap-weighted mean and dividing by the equal-weighted standard deviation, so
+/// the cap-weighted market has zero exposure to the score. This is the same
+/// convention [`CompositeFactor`](crate::CompositeFactor) uses, and is useful
+/// for turning raw descriptors, such as the output of
+/// [`historical_beta`](crate::historical_beta), into style scores.
+///
+/// # Arguments
+/// * `df` - LazyFrame with | date | symbol | `score` | and any other columns
+/// * `score` - Column to standardize in place
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap | used as weights
+/// * `winsor_factor` - Cross-sectional winsorization percentile (None to disable)
+///
+/// # Returns
+/// LazyFrame with the standardized score and the other input columns.
+pub fn standardize_scores(
+    df: LazyFrame,
+    score: &str,
+    mkt_cap_df: LazyFrame,
+    winsor_factor: Option<f64>,
+) -> LazyFrame {
+    let caps = mkt_cap_df.select([
+        col("date"),
+        col("symbol"),
+        col("market_cap").cast(DataType::Float64).alias(WEIGHT_COLUMN),
+    ]);
+    let df = df
+        .join(
+            caps,
+            [col("date"), col("symbol")],
+            [col("date"), col("symbol")],
+            JoinArgs::new(JoinType::Inner),
+        )
+        .filter(col(score).is_finite().and(col(WEIGHT_COLUMN).is_not_null()));
+    let df = match winsor_factor {
+        Some(p) => winsorize_xsection(df, &[score], "date", p),
+        None => df,
+    };
+    df.with_column(weighted_center_xsection(score, WEIGHT_COLUMN, "date", true).alias(score))
+        .drop([WEIGHT_COLUMN])
+}
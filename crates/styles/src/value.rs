@@ -0,0 +1,74 @@
+//! Value descriptor.
+
+use polars::prelude::*;
+
+use crate::CompositeFactor;
+
+/// Compute value scores from one or more valuation ratios.
+///
+/// An equal-weighted [`CompositeFactor`] of the descriptors (e.g. `book_price`,
+/// `sales_price`, `cf_price`): each is winsorized and standardized per date,
+/// the available descriptors are averaged per asset, and the composite is
+/// standardized again. Assets with no descriptor or no market cap on a date
+/// are dropped.
+///
+/// # Arguments
+/// * `value_df` - LazyFrame with | date | symbol | descriptors... |
+/// * `mkt_cap_df` - LazyFrame with | date | symbol | market_cap |, weighting the means
+/// * `descriptors` - Valuation ratio columns, higher meaning cheaper
+/// * `winsor_factor` - Cross-sectional winsorization percentile (None to disable)
+///
+/// # Returns
+/// LazyFrame with | date | symbol | val_score |, standardized per date.
+pub fn value(
+    value_df: LazyFrame,
+    mkt_cap_df: LazyFrame,
+    descriptors: &[&str],
+    winsor_factor: Option<f64>,
+) -> LazyFrame {
+    let composite = descriptors
+        .iter()
+        .fold(CompositeFactor::new("val_score"), |c, &d| c.with_descriptor(d, 1.0));
+    let composite = match winsor_factor {
+        Some(p) => composite.with_winsorization(p),
+        None => composite,
+    };
+
+    let caps = mkt_cap_df.select([col("date"), col("symbol"), col("market_cap")]);
+    composite.build(value_df.join(
+        caps,
+        [col("date"), col("symbol")],
+        [col("date"), col("symbol")],
+        JoinArgs::new(JoinType::Inner),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_averages_available_descriptors() {
+        let df = df! {
+            "date" => &[1, 1, 1, 1],
+            "symbol" => &["A", "B", "C", "D"],
+            "book_price" => &[Some(0.2), Some(0.5), Some(0.8), None],
+            "sales_price" => &[Some(1.0), Some(2.0), Some(3.0), Some(2.5)],
+        }
+        .unwrap()
+        .lazy();
+
+        let caps = df.clone().select([col("date"), col("symbol"), lit(1e9).alias("market_cap")]);
+        let result = value(df, caps, &["book_price", "sales_price"], None)
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let scores: Vec<f64> =
+            result.column("val_score").unwrap().f64().unwrap().into_no_null_iter().collect();
+
+        // D has only a sales ratio but is still scored
+        assert_eq!(scores.len(), 4);
+        assert!(scores[0] < scores[1] && scores[1] < scores[2]);
+        assert!(scores[3] > scores[1]);
+    }
+}
@@ -3,7 +3,7 @@
 //! This example demonstrates the data utility functions available in toraniko
 //! using real Yahoo Finance data:
 //! - `fill_features`: Forward-fill missing values within groups
-//! - `smooth_features`: Apply rolling mean smoothing
+//! - `smooth_features`: Apply trailing rolling mean smoothing (no look-ahead)
 //! - `top_n_by_group`: Select top N assets by a metric per group
 //!
 //! Based on the original Python toraniko implementation:
@@ -13,7 +13,9 @@ use std::collections::HashMap;
 
 use polars::prelude::*;
 use time::{Duration, OffsetDateTime};
-use toraniko::utils::{fill_features, smooth_features, top_n_by_group};
+use toraniko::utils::{
+    SmoothConfig, fill_features, smooth_features, smooth_features_with, top_n_by_group,
+};
 use yahoo_finance_api as yahoo;
 
 /// Broad stock universe for demonstrating filtering
@@ -185,13 +187,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Smooth the daily range (which can be noisy) using a 5-day rolling mean
     println!("Applying 5-day rolling mean smoothing to daily_range...\n");
 
+    // Smoothing replaces the column in place, so smooth copies to keep the raw series
     let smoothed = smooth_features(
-        full_df.lazy(),
-        &["daily_range"],
+        full_df.lazy().with_columns([
+            col("daily_range").alias("daily_range_smooth"),
+            col("daily_range").alias("daily_range_ewm"),
+        ]),
+        &["daily_range_smooth"],
         "date",
         "symbol",
         5, // 5-day window
-    )
+    );
+
+    // Exponentially weighted alternative: 10-day window, 3-day half-life
+    let smoothed = smooth_features_with(
+        smoothed,
+        &["daily_range_ewm"],
+        "date",
+        "symbol",
+        &SmoothConfig::exponential(10, 3).with_min_periods(3),
+    )?
     .collect()?;
 
     // Show before/after for a single symbol
@@ -204,6 +219,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             col("symbol"),
             col("daily_range").alias("raw_range"),
             col("daily_range_smooth").alias("smoothed_range"),
+            col("daily_range_ewm").alias("ewm_range"),
         ])
         .sort(["date"], Default::default())
         .limit(15)
@@ -368,7 +368,11 @@ fn estimate_factor_returns(
     );
 
     // Configure the estimator
-    let config = EstimatorConfig { winsor_factor: Some(0.05), residualize_styles: true };
+    let config = EstimatorConfig {
+        winsor_factor: Some(0.05),
+        residualize_styles: true,
+        ..Default::default()
+    };
 
     let estimator = FactorReturnsEstimator::with_config(config);
 
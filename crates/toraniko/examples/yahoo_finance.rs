@@ -260,7 +260,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("=== Factor Returns Estimation ===\n");
 
-    let config = EstimatorConfig { winsor_factor: Some(0.05), residualize_styles: true };
+    let config = EstimatorConfig {
+        winsor_factor: Some(0.05),
+        residualize_styles: true,
+        ..Default::default()
+    };
 
     let estimator = FactorReturnsEstimator::with_config(config);
     println!("Estimator configuration:");
@@ -0,0 +1,218 @@
+//! `attribute`: return attribution for a symbol or a portfolio.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use polars::prelude::*;
+use toraniko::{
+    io::{Artifact, ArtifactStore, DataError},
+    model::compute_attribution,
+    primitives::Date,
+};
+
+use crate::{CliResult, DateRange, StoreArgs};
+
+/// Symbol under which a portfolio's aggregated returns are attributed.
+const PORTFOLIO: &str = "PORTFOLIO";
+
+#[derive(Debug, Args)]
+pub(crate) struct AttributeArgs {
+    #[command(flatten)]
+    target: Target,
+    #[command(flatten)]
+    store: StoreArgs,
+    #[command(flatten)]
+    range: DateRange,
+}
+
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+struct Target {
+    /// Symbol to attribute.
+    #[arg(long)]
+    symbol: Option<String>,
+    /// `symbol,weight` holdings file (CSV or Parquet); weights are rescaled to
+    /// sum to one over the holdings present on each date.
+    #[arg(long)]
+    portfolio: Option<PathBuf>,
+}
+
+/// Attribute returns over the stored dates in range and print the summary.
+pub(crate) fn run(args: &AttributeArgs) -> CliResult<()> {
+    let store = args.store.open();
+    let (start, end) = (args.range.start, args.range.end);
+    let factor_returns = store.scan(Artifact::FactorReturns, start, end)?.collect()?;
+    let residuals = store.scan(Artifact::Residuals, start, end)?.collect()?;
+    let exposures = store.scan(Artifact::Exposures, start, end)?.collect()?;
+
+    let (symbol, residuals, exposures) = match (&args.target.symbol, &args.target.portfolio) {
+        (Some(symbol), _) => (symbol.to_uppercase(), residuals, exposures),
+        (None, Some(path)) => {
+            let weights = read_weights(path)?;
+            let (residuals, exposures) = aggregate(&weights, residuals, exposures)?;
+            print_ex_ante_risk(&store, end, &weights)?;
+            (PORTFOLIO.to_string(), residuals, exposures)
+        }
+        (None, None) => unreachable!("clap requires --symbol or --portfolio"),
+    };
+
+    compute_attribution(&symbol, &factor_returns, &residuals, &exposures, &exposures)?
+        .print_summary();
+    Ok(())
+}
+
+/// Read holdings and rescale the weights to sum to one.
+fn read_weights(path: &Path) -> CliResult<DataFrame> {
+    let raw = if path.extension().is_some_and(|ext| ext == "parquet") {
+        ParquetReader::new(std::fs::File::open(path)?).finish()?
+    } else {
+        CsvReadOptions::default()
+            .with_has_header(true)
+            .try_into_reader_with_file_path(Some(path.to_path_buf()))?
+            .finish()?
+    };
+    let weights = raw
+        .lazy()
+        .select([
+            col("symbol").cast(DataType::String).str().to_uppercase(),
+            col("weight").cast(DataType::Float64),
+        ])
+        .collect()?;
+
+    let total = weights.column("weight")?.f64()?.sum().unwrap_or(0.0);
+    if total.abs() < 1e-12 {
+        return Err("portfolio weights sum to zero".into());
+    }
+    if (total - 1.0).abs() > 1e-6 {
+        println!("Rescaling portfolio weights (sum {total:.4}) to one");
+    }
+    Ok(weights.lazy().with_column(col("weight") / lit(total)).collect()?)
+}
+
+/// Weighted residuals and exposures of the holdings per date, as one symbol.
+///
+/// On each date the weights are renormalized over the holdings present in
+/// the store, so the aggregated exposures always carry the full portfolio;
+/// holdings missing on some dates are reported. Every exposure column
+/// (sectors, countries and styles) is aggregated.
+fn aggregate(
+    weights: &DataFrame,
+    residuals: DataFrame,
+    exposures: DataFrame,
+) -> CliResult<(DataFrame, DataFrame)> {
+    report_missing_holdings(weights, &residuals)?;
+
+    let holdings = |df: DataFrame| {
+        df.lazy()
+            .join(
+                weights.clone().lazy(),
+                [col("symbol")],
+                [col("symbol")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .with_column((col("weight") / col("weight").sum().over([col("date")])).alias("weight"))
+    };
+
+    let residuals = holdings(residuals)
+        .group_by([col("date")])
+        .agg([(col("residual_return") * col("weight")).sum()])
+        .with_column(lit(PORTFOLIO).alias("symbol"))
+        .sort(["date"], SortMultipleOptions::default())
+        .collect()?;
+
+    let columns: Vec<Expr> = exposures
+        .get_columns()
+        .iter()
+        .filter(|c| c.name() != "date" && c.name() != "symbol" && c.dtype().is_float())
+        .map(|c| (col(c.name().clone()) * col("weight")).sum().alias(c.name().clone()))
+        .collect();
+    let exposures = holdings(exposures)
+        .group_by([col("date")])
+        .agg(columns)
+        .with_column(lit(PORTFOLIO).alias("symbol"))
+        .sort(["date"], SortMultipleOptions::default())
+        .collect()?;
+
+    Ok((residuals, exposures))
+}
+
+/// Warn about holdings that are not in the store on every date.
+fn report_missing_holdings(weights: &DataFrame, residuals: &DataFrame) -> CliResult<()> {
+    let n_dates = residuals.column("date")?.n_unique()?;
+    let coverage = residuals
+        .clone()
+        .lazy()
+        .group_by([col("symbol")])
+        .agg([col("date").n_unique().cast(DataType::UInt32).alias("dates")]);
+    let missing = weights
+        .clone()
+        .lazy()
+        .join(coverage, [col("symbol")], [col("symbol")], JoinArgs::new(JoinType::Left))
+        .with_column(col("dates").fill_null(lit(0)))
+        .filter(col("dates").lt(lit(n_dates as u32)))
+        .sort(["symbol"], SortMultipleOptions::default())
+        .collect()?;
+    if missing.height() == 0 {
+        return Ok(());
+    }
+
+    let symbols = missing.column("symbol")?.str()?;
+    let dates = missing.column("dates")?.u32()?;
+    let listed: Vec<String> = symbols
+        .iter()
+        .zip(dates.iter())
+        .map(|(s, d)| format!("{} ({} of {n_dates} dates)", s.unwrap_or(""), d.unwrap_or(0)))
+        .collect();
+    println!(
+        "Warning: {} holdings are missing on some dates; weights are renormalized over the \
+         holdings present on each date: {}",
+        missing.height(),
+        listed.join(", ")
+    );
+    Ok(())
+}
+
+/// Print the ex-ante volatility from the latest snapshot up to `end`, if any.
+///
+/// Holdings not in the snapshot are listed and left out of the estimate.
+fn print_ex_ante_risk(
+    store: &ArtifactStore,
+    end: Option<Date>,
+    weights: &DataFrame,
+) -> CliResult<()> {
+    let dates = store.dates(Artifact::Covariance)?;
+    let Some(&date) = dates.iter().rev().find(|d| end.is_none_or(|e| **d <= e)) else {
+        return Ok(());
+    };
+    let snapshot = match store.read_snapshot(date) {
+        Ok(snapshot) => snapshot,
+        Err(DataError::MissingFile(_)) => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let symbols = weights.column("symbol")?.str()?;
+    let values = weights.column("weight")?.f64()?;
+    let mut holdings: Vec<(&str, f64)> = Vec::new();
+    let mut names: Vec<&str> = Vec::new();
+    for (symbol, weight) in symbols.iter().zip(values.iter()) {
+        let (Some(symbol), Some(weight)) = (symbol, weight) else { continue };
+        if snapshot.symbols.iter().any(|s| s == symbol) {
+            holdings.push((symbol, weight));
+        } else {
+            names.push(symbol);
+        }
+    }
+    if !names.is_empty() {
+        println!(
+            "Warning: {} holdings are not in the risk model as of {date} and are left out of \
+             the ex-ante risk: {}",
+            names.len(),
+            names.join(", ")
+        );
+    }
+
+    let variance = snapshot.portfolio_variance(&holdings)?;
+    let annual = variance * snapshot.frequency().periods_per_year();
+    println!("Ex-ante volatility as of {date}: {:.2}% annualized", annual.sqrt() * 100.0);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! `estimate`: factor returns and residuals to the store.
+
+use clap::Args;
+use polars::prelude::IntoLazy;
+use toraniko::{
+    io::Artifact,
+    model::{EstimatorConfig, FactorReturnsEstimator},
+};
+
+use crate::{CliResult, Frequency, StoreArgs, inputs::InputArgs};
+
+#[derive(Debug, Args)]
+pub(crate) struct EstimateArgs {
+    #[command(flatten)]
+    inputs: InputArgs,
+    #[command(flatten)]
+    store: StoreArgs,
+    /// Regression frequency.
+    #[arg(long, value_enum, default_value_t = Frequency::Daily)]
+    frequency: Frequency,
+    /// Cross-sectional winsorization percentile of asset returns.
+    #[arg(long, default_value_t = 0.05)]
+    winsor_factor: f64,
+}
+
+/// Estimate and write factor returns, residuals and exposures.
+///
+/// The exposures written are the ones the regression used (styles
+/// orthogonalized, dropped sectors zeroed), so risk built from the store is
+/// consistent with the factor returns.
+///
+/// Writing replaces only the dates being written, so rerunning over a later
+/// window appends to the store. A store estimated with a different
+/// configuration is refused rather than mixed.
+pub(crate) async fn run(args: &EstimateArgs) -> CliResult<()> {
+    let config = EstimatorConfig {
+        winsor_factor: Some(args.winsor_factor),
+        residualize_styles: true,
+        frequency: args.frequency.into(),
+        ..Default::default()
+    };
+    let store = args.store.open();
+    if let Some(stored) = store.read_estimator_config()?
+        && stored != config
+    {
+        return Err(format!(
+            "{} was estimated with {stored:?}, not {config:?}; use a new store or rerun with \
+             the same settings",
+            store.root().display()
+        )
+        .into());
+    }
+    let estimator = FactorReturnsEstimator::with_config(config.clone());
+
+    let inputs = args.inputs.load().await?;
+
+    let (factor_returns, residuals, exposures) = estimator.estimate_with_exposures(
+        inputs.returns,
+        inputs.market_caps,
+        inputs.sectors.lazy(),
+        inputs.styles.lazy(),
+    )?;
+
+    let dates = store.write(Artifact::FactorReturns, &factor_returns)?;
+    store.write(Artifact::Residuals, &residuals)?;
+    store.write(Artifact::Exposures, &exposures)?;
+    store.write_estimator_config(&config)?;
+
+    match (dates.first(), dates.last()) {
+        (Some(first), Some(last)) => println!(
+            "Estimated {} dates ({first} to {last}) into {}",
+            dates.len(),
+            store.root().display()
+        ),
+        _ => println!("No dates estimated"),
+    }
+    Ok(())
+}
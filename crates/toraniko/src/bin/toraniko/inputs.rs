@@ -0,0 +1,152 @@
+//! Estimation inputs from a local data directory or Yahoo Finance.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Months;
+use clap::Args;
+use polars::prelude::*;
+use toraniko::{
+    io::{load_dataset, load_dataset_with_sector_map},
+    primitives::Date,
+    styles::{MomentumConfig, momentum, size, value},
+};
+
+use crate::{CliResult, yahoo};
+
+/// Extra history loaded before the estimation window, covering the momentum lookback.
+const LOOKBACK_YEARS: u32 = 1;
+
+/// Valuation ratios combined into the value score.
+const VALUE_DESCRIPTORS: &[&str] = &["book_price", "sales_price", "cf_price"];
+
+/// Where the estimation inputs come from.
+#[derive(Debug, Args)]
+pub(crate) struct InputArgs {
+    /// Directory laid out like `data/` (CSV or Parquet); without it, data is
+    /// downloaded from Yahoo Finance.
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+    /// `symbol,sector` mapping file; replaces `sectors.csv` with `--data-dir`,
+    /// or the built-in universe when downloading.
+    #[arg(long)]
+    sectors: Option<PathBuf>,
+    /// Extra symbols to download alongside the universe (sector "Other").
+    /// Only applies when downloading.
+    #[arg(long = "include", value_name = "SYMBOL", conflicts_with = "data_dir")]
+    include: Vec<String>,
+    /// Number of years, ending at the last date, to estimate.
+    #[arg(long, default_value_t = 5)]
+    years: u32,
+}
+
+/// Frames passed to the estimator, restricted to the estimation window.
+pub(crate) struct Inputs {
+    pub(crate) returns: LazyFrame,
+    pub(crate) market_caps: LazyFrame,
+    /// | date | symbol | sector_* |
+    pub(crate) sectors: DataFrame,
+    /// | date | symbol | *_score |
+    pub(crate) styles: DataFrame,
+}
+
+/// Raw panels before style scores are computed.
+pub(crate) struct RawInputs {
+    pub(crate) returns: LazyFrame,
+    pub(crate) market_caps: LazyFrame,
+    /// Valuation ratios for the value score, if available.
+    pub(crate) valuations: Option<LazyFrame>,
+    /// | date | symbol | sector_* |
+    pub(crate) sectors: LazyFrame,
+}
+
+impl InputArgs {
+    /// Load the inputs and compute style scores.
+    ///
+    /// Style scores are computed on the full history and the result is cut
+    /// to the last `years` of dates, so momentum is available from the start
+    /// of the window when enough history exists.
+    pub(crate) async fn load(&self) -> CliResult<Inputs> {
+        let raw = match &self.data_dir {
+            Some(dir) => load_local(dir, self.sectors.as_deref())?,
+            None => {
+                yahoo::fetch(self.sectors.as_deref(), &self.include, self.years + LOOKBACK_YEARS)
+                    .await?
+            }
+        };
+
+        let last = raw.returns.clone().select([col("date").max()]).collect()?;
+        let last: Date =
+            last.column("date")?.date()?.as_date_iter().next().flatten().ok_or("no returns")?;
+        let start =
+            last.checked_sub_months(Months::new(self.years * 12)).ok_or("invalid --years")?;
+        let window = |lf: LazyFrame| lf.filter(col("date").gt_eq(lit(start)));
+
+        let styles = window(style_scores(&raw)?).collect()?;
+        Ok(Inputs {
+            returns: window(raw.returns),
+            market_caps: window(raw.market_caps),
+            sectors: window(raw.sectors).collect()?,
+            styles,
+        })
+    }
+}
+
+/// Load the panels from a local directory laid out like `data/`.
+fn load_local(dir: &Path, sector_map: Option<&Path>) -> CliResult<RawInputs> {
+    let dataset = match sector_map {
+        Some(path) => load_dataset_with_sector_map(dir, path)?,
+        None => load_dataset(dir)?,
+    };
+    println!("Loaded data from {}", dir.display());
+
+    // Drop the optional `sector` label; only exposures enter the model
+    let mut sectors = dataset.sectors;
+    let mut columns = vec![col("date"), col("symbol")];
+    columns.extend(
+        sectors
+            .collect_schema()?
+            .iter_names()
+            .filter(|name| name.starts_with("sector_"))
+            .map(|name| col(name.clone())),
+    );
+
+    Ok(RawInputs {
+        returns: dataset.returns,
+        market_caps: dataset.market_caps,
+        valuations: dataset.valuations,
+        sectors: sectors.select(columns),
+    })
+}
+
+/// Momentum, size and (when valuation ratios are available) value scores.
+fn style_scores(raw: &RawInputs) -> PolarsResult<LazyFrame> {
+    let mut scores = vec![
+        momentum(raw.returns.clone(), raw.market_caps.clone(), &MomentumConfig::default()),
+        size(raw.market_caps.clone()),
+    ];
+    if let Some(valuations) = &raw.valuations {
+        let schema = valuations.clone().collect_schema()?;
+        let descriptors: Vec<&str> =
+            VALUE_DESCRIPTORS.iter().copied().filter(|name| schema.contains(name)).collect();
+        if !descriptors.is_empty() {
+            scores.push(value(
+                valuations.clone(),
+                raw.market_caps.clone(),
+                &descriptors,
+                Some(0.05),
+            ));
+        }
+    }
+
+    Ok(scores
+        .into_iter()
+        .reduce(|acc, lf| {
+            acc.join(
+                lf,
+                [col("date"), col("symbol")],
+                [col("date"), col("symbol")],
+                JoinArgs::new(JoinType::Inner),
+            )
+        })
+        .unwrap_or_default())
+}
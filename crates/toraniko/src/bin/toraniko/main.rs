@@ -0,0 +1,121 @@
+//! Command line interface for the toraniko factor model.
+//!
+//! Each subcommand reads and writes the persisted formats of `toraniko::io`,
+//! so a pipeline runs as separate steps against one store directory:
+//!
+//! ```text
+//! toraniko estimate --data-dir data --store output
+//! toraniko risk --store output
+//! toraniko attribute --store output --symbol AAPL
+//! toraniko report --store output
+//! ```
+
+mod attribute;
+mod estimate;
+mod inputs;
+mod report;
+mod risk;
+mod yahoo;
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use toraniko::{
+    io::{ArtifactStore, StorageFormat},
+    model::EstimationFrequency,
+    primitives::Date,
+};
+
+/// Result type of every subcommand.
+type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Characteristic factor model: estimate factor returns, build risk, attribute returns.
+#[derive(Debug, Parser)]
+#[command(name = "toraniko", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Estimate factor returns and residuals and write them to the store.
+    Estimate(estimate::EstimateArgs),
+    /// Build the factor covariance and specific risk from stored outputs.
+    Risk(risk::RiskArgs),
+    /// Attribute a symbol's or a portfolio's return to the factors.
+    Attribute(attribute::AttributeArgs),
+    /// Summarize the stored factor returns and the latest risk model.
+    Report(report::ReportArgs),
+}
+
+/// Location and format of the artifact store, shared by every subcommand.
+#[derive(Debug, Args)]
+struct StoreArgs {
+    /// Directory holding the model outputs.
+    #[arg(long, default_value = "output")]
+    store: PathBuf,
+    /// File format of the stored artifacts.
+    #[arg(long, value_enum, default_value_t = Format::Parquet)]
+    format: Format,
+}
+
+impl StoreArgs {
+    fn open(&self) -> ArtifactStore {
+        let format = match self.format {
+            Format::Parquet => StorageFormat::Parquet,
+            Format::Ipc => StorageFormat::Ipc,
+        };
+        ArtifactStore::new(&self.store).with_format(format)
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Parquet,
+    Ipc,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl From<Frequency> for EstimationFrequency {
+    fn from(frequency: Frequency) -> Self {
+        match frequency {
+            Frequency::Daily => Self::Daily,
+            Frequency::Weekly => Self::Weekly,
+            Frequency::Monthly => Self::Monthly,
+        }
+    }
+}
+
+/// Optional `--start` / `--end` bounds on stored dates.
+#[derive(Debug, Args)]
+struct DateRange {
+    /// First date to include (YYYY-MM-DD).
+    #[arg(long)]
+    start: Option<Date>,
+    /// Last date to include (YYYY-MM-DD).
+    #[arg(long)]
+    end: Option<Date>,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Estimate(args) => estimate::run(&args).await,
+        Command::Risk(args) => risk::run(&args),
+        Command::Attribute(args) => attribute::run(&args),
+        Command::Report(args) => report::run(&args),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
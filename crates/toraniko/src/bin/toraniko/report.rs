@@ -0,0 +1,87 @@
+//! `report`: summary of the stored model.
+
+use clap::Args;
+use polars::prelude::*;
+use toraniko::io::{Artifact, DataError};
+
+use crate::{CliResult, DateRange, StoreArgs};
+
+#[derive(Debug, Args)]
+pub(crate) struct ReportArgs {
+    #[command(flatten)]
+    store: StoreArgs,
+    #[command(flatten)]
+    range: DateRange,
+}
+
+/// Print coverage, annualized factor performance and the latest risk model.
+pub(crate) fn run(args: &ReportArgs) -> CliResult<()> {
+    let store = args.store.open();
+    let (start, end) = (args.range.start, args.range.end);
+    let config = store.read_estimator_config()?.unwrap_or_default();
+    let periods = config.frequency.periods_per_year();
+
+    let factor_returns = store.scan(Artifact::FactorReturns, start, end)?;
+    let coverage = store
+        .scan(Artifact::Residuals, start, end)?
+        .group_by([col("date")])
+        .agg([len().alias("assets")])
+        .select([
+            col("date").min().alias("first"),
+            col("date").max().alias("last"),
+            len().alias("dates"),
+            col("assets").cast(DataType::Float64).mean().alias("assets"),
+        ])
+        .collect()?;
+    let performance = factor_returns
+        .group_by([col("factor")])
+        .agg([
+            (col("factor_return").mean() * lit(periods)).alias("return"),
+            (col("factor_return").std(1) * lit(periods.sqrt())).alias("vol"),
+        ])
+        .with_column((col("return") / col("vol")).alias("sharpe"))
+        .sort(["factor"], SortMultipleOptions::default())
+        .collect()?;
+
+    println!("Store:       {}", store.root().display());
+    println!("Frequency:   {:?}", config.frequency);
+    println!(
+        "Dates:       {} ({} to {})",
+        coverage.column("dates")?.get(0)?,
+        coverage.column("first")?.get(0)?,
+        coverage.column("last")?.get(0)?
+    );
+    println!("Assets/date: {:.1}", coverage.column("assets")?.f64()?.get(0).unwrap_or(0.0));
+
+    println!("\n{:<24} {:>12} {:>12} {:>8}", "Factor", "Ann. Return", "Ann. Vol", "Sharpe");
+    println!("{:-<24} {:-^12} {:-^12} {:-^8}", "", "", "", "");
+    let names = performance.column("factor")?.str()?;
+    let returns = performance.column("return")?.f64()?;
+    let vols = performance.column("vol")?.f64()?;
+    let sharpes = performance.column("sharpe")?.f64()?;
+    for i in 0..performance.height() {
+        println!(
+            "{:<24} {:>11.2}% {:>11.2}% {:>8.2}",
+            names.get(i).unwrap_or(""),
+            returns.get(i).unwrap_or(f64::NAN) * 100.0,
+            vols.get(i).unwrap_or(f64::NAN) * 100.0,
+            sharpes.get(i).unwrap_or(f64::NAN)
+        );
+    }
+
+    let dates = store.dates(Artifact::Covariance)?;
+    match dates.iter().rev().find(|d| end.is_none_or(|e| **d <= e)) {
+        Some(&date) => match store.read_snapshot(date) {
+            Ok(snapshot) => println!(
+                "\nRisk model as of {date}: {} factors, {} assets (toraniko {})",
+                snapshot.factors.len(),
+                snapshot.metadata.universe_size,
+                snapshot.metadata.code_version
+            ),
+            Err(DataError::MissingFile(_)) => println!("\nCovariance as of {date} (no snapshot)"),
+            Err(err) => return Err(err.into()),
+        },
+        None => println!("\nNo risk model; run `risk` to build one"),
+    }
+    Ok(())
+}
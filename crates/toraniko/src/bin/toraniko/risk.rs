@@ -0,0 +1,90 @@
+//! `risk`: factor covariance and specific risk from stored outputs.
+
+use clap::Args;
+use toraniko::{
+    io::{Artifact, RiskModelSnapshot},
+    model::{RiskConfig, RiskState},
+    primitives::Date,
+};
+
+use crate::{CliResult, StoreArgs};
+
+#[derive(Debug, Args)]
+pub(crate) struct RiskArgs {
+    #[command(flatten)]
+    store: StoreArgs,
+    /// As-of date (YYYY-MM-DD); defaults to the last stored date.
+    #[arg(long)]
+    date: Option<Date>,
+    /// Half-life of the factor covariance, in model periods.
+    #[arg(long, default_value_t = RiskConfig::default().factor_half_life)]
+    factor_half_life: usize,
+    /// Half-life of the specific variances, in model periods.
+    #[arg(long, default_value_t = RiskConfig::default().specific_half_life)]
+    specific_half_life: usize,
+    /// Rebuild the risk state from the full history instead of updating the stored one.
+    #[arg(long)]
+    rebuild: bool,
+}
+
+/// Build the risk model as of a date and write the covariance, the risk
+/// state for incremental updates, and a snapshot.
+///
+/// A stored risk state with the same configuration is updated with the dates
+/// after its last date only. A state already past the as-of date is left in
+/// place and the model is rebuilt from the full history instead.
+pub(crate) fn run(args: &RiskArgs) -> CliResult<()> {
+    let store = args.store.open();
+    let estimator = store.read_estimator_config()?.unwrap_or_default();
+    let date = match args.date {
+        Some(date) => date,
+        None => *store
+            .dates(Artifact::FactorReturns)?
+            .last()
+            .ok_or("no factor returns in the store; run `estimate` first")?,
+    };
+
+    let exposures =
+        store.scan(Artifact::Exposures, Some(date), Some(date))?.collect()?.drop("date")?;
+
+    let risk = RiskConfig {
+        factor_half_life: args.factor_half_life,
+        specific_half_life: args.specific_half_life,
+        frequency: estimator.frequency,
+    };
+    let stored = if args.rebuild {
+        None
+    } else {
+        store.read_risk_state()?.filter(|state| *state.config() == risk)
+    };
+    let ahead = stored.as_ref().and_then(RiskState::last_date).is_some_and(|last| last > date);
+    let mut state = match stored {
+        Some(state) if !ahead => state,
+        _ => RiskState::new(risk)?,
+    };
+
+    // Dates up to the state's last date are skipped by the update
+    let start = state.last_date();
+    let factor_returns = store.scan(Artifact::FactorReturns, start, Some(date))?.collect()?;
+    let residuals = store.scan(Artifact::Residuals, start, Some(date))?.collect()?;
+    state.update(&factor_returns, &residuals)?;
+    let covariance = state.covariance()?;
+    let specific_variance = state.specific_variance()?;
+
+    store.write_covariance(date, &covariance)?;
+    if !ahead {
+        store.write_risk_state(&state)?;
+    }
+    let snapshot =
+        RiskModelSnapshot::new(date, &exposures, &covariance, &specific_variance, estimator, risk)?;
+    let path = store.write_snapshot(&snapshot)?;
+
+    println!("Risk model as of {date} ({} assets): {}", snapshot.symbols.len(), path.display());
+    println!("{:<24} {:>14}", "Factor", "Ann. Vol");
+    println!("{:-<24} {:-^14}", "", "");
+    let annualized = covariance.annualized();
+    for (i, factor) in covariance.factors().iter().enumerate() {
+        println!("{factor:<24} {:>13.2}%", annualized[[i, i]].sqrt() * 100.0);
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Market data download from Yahoo Finance.
+
+use std::{collections::HashMap, path::Path};
+
+use polars::prelude::*;
+use time::{Duration, OffsetDateTime};
+use toraniko::io::load_sector_map;
+use yahoo_finance_api as yahoo;
+
+use crate::{CliResult, inputs::RawInputs};
+
+/// Reference universe organized by sector (expanded for better factor estimation).
+const TECH_STOCKS: &[&str] =
+    &["AAPL", "MSFT", "GOOGL", "META", "NVDA", "AMD", "INTC", "CRM", "ADBE", "ORCL"];
+const HEALTHCARE_STOCKS: &[&str] =
+    &["JNJ", "UNH", "PFE", "MRK", "ABBV", "TMO", "ABT", "LLY", "BMY", "AMGN"];
+const FINANCE_STOCKS: &[&str] =
+    &["JPM", "BAC", "WFC", "GS", "MS", "C", "BLK", "SCHW", "AXP", "USB"];
+
+/// Built-in symbol to sector map.
+fn default_sectors() -> HashMap<String, String> {
+    [(TECH_STOCKS, "Technology"), (HEALTHCARE_STOCKS, "Healthcare"), (FINANCE_STOCKS, "Finance")]
+        .into_iter()
+        .flat_map(|(symbols, sector)| {
+            symbols.iter().map(move |symbol| ((*symbol).to_string(), sector.to_string()))
+        })
+        .collect()
+}
+
+/// Read a symbol to sector mapping file into a lookup.
+fn read_sector_map(path: &Path) -> CliResult<HashMap<String, String>> {
+    let map = load_sector_map(path)?;
+    let symbols = map.column("symbol")?.str()?;
+    let sectors = map.column("sector")?.str()?;
+    Ok(symbols
+        .iter()
+        .zip(sectors.iter())
+        .filter_map(|(symbol, sector)| Some((symbol?.to_uppercase(), sector?.to_string())))
+        .collect())
+}
+
+/// Download `years` of daily quotes for the universe.
+///
+/// The universe is the sector map (or the built-in 30 stocks) plus the
+/// `include` symbols. Market caps and valuation ratios are crude proxies
+/// built from the quotes.
+pub(crate) async fn fetch(
+    sector_map: Option<&Path>,
+    include: &[String],
+    years: u32,
+) -> CliResult<RawInputs> {
+    let mut sector_of = match sector_map {
+        Some(path) => read_sector_map(path)?,
+        None => default_sectors(),
+    };
+    for symbol in include {
+        sector_of.entry(symbol.to_uppercase()).or_insert_with(|| "Other".to_string());
+    }
+    let mut symbols: Vec<&String> = sector_of.keys().collect();
+    symbols.sort();
+
+    let provider = yahoo::YahooConnector::new()?;
+
+    let end = OffsetDateTime::now_utc();
+    let start = end - Duration::days(i64::from(years) * 366);
+
+    print!("Fetching data for {} stocks", symbols.len());
+
+    let mut stock_data: HashMap<String, Vec<yahoo::Quote>> = HashMap::new();
+    let mut count = 0;
+
+    for symbol in symbols {
+        match provider.get_quote_history(symbol, start, end).await {
+            Ok(response) => {
+                if let Ok(quotes) = response.quotes()
+                    && !quotes.is_empty()
+                {
+                    stock_data.insert(symbol.clone(), quotes);
+                    count += 1;
+                    if count % 10 == 0 {
+                        print!(".");
+                    }
+                }
+            }
+            Err(_) => continue,
+        }
+    }
+    println!(" done ({} stocks loaded)", stock_data.len());
+
+    // Build DataFrames
+    let mut dates: Vec<i64> = Vec::new();
+    let mut syms: Vec<String> = Vec::new();
+    let mut returns: Vec<f64> = Vec::new();
+    let mut market_caps: Vec<f64> = Vec::new();
+    let mut sectors: Vec<String> = Vec::new();
+    let mut book_price_proxy: Vec<f64> = Vec::new();
+    let mut sales_price_proxy: Vec<f64> = Vec::new();
+    let mut cf_price_proxy: Vec<f64> = Vec::new();
+
+    for (symbol, quotes) in &stock_data {
+        let sector = &sector_of[symbol];
+
+        for i in 1..quotes.len() {
+            let prev_close = quotes[i - 1].adjclose;
+            let curr_close = quotes[i].adjclose;
+            let daily_return = (curr_close - prev_close) / prev_close;
+
+            dates.push(quotes[i].timestamp);
+            syms.push(symbol.clone());
+            returns.push(daily_return);
+            sectors.push(sector.clone());
+
+            let volume = quotes[i].volume as f64;
+            let close = quotes[i].close;
+            market_caps.push(volume * close);
+
+            let high = quotes[i].high;
+            let low = quotes[i].low;
+            book_price_proxy.push(low / close);
+            sales_price_proxy.push((high - low) / close);
+            cf_price_proxy.push(volume / (close * 1e6));
+        }
+    }
+
+    // Convert timestamps to dates
+    let dates_ms: Vec<i64> = dates.iter().map(|t| t * 1000).collect();
+    let dates_series = Series::new("timestamp".into(), &dates_ms);
+    let dates_datetime = dates_series.cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+    let dates_col = dates_datetime.cast(&DataType::Date)?;
+
+    let sector_labels = DataFrame::new(vec![
+        dates_col.clone().with_name("date".into()).into(),
+        Column::new("symbol".into(), syms.clone()),
+        Column::new("sector".into(), sectors),
+    ])?;
+
+    let returns_df = DataFrame::new(vec![
+        dates_col.clone().with_name("date".into()).into(),
+        Column::new("symbol".into(), syms.clone()),
+        Column::new("asset_returns".into(), returns),
+    ])?;
+
+    let mkt_cap_df = DataFrame::new(vec![
+        dates_col.clone().with_name("date".into()).into(),
+        Column::new("symbol".into(), syms.clone()),
+        Column::new("market_cap".into(), market_caps),
+    ])?;
+
+    let fundamentals_df = DataFrame::new(vec![
+        dates_col.with_name("date".into()).into(),
+        Column::new("symbol".into(), syms),
+        Column::new("book_price".into(), book_price_proxy),
+        Column::new("sales_price".into(), sales_price_proxy),
+        Column::new("cf_price".into(), cf_price_proxy),
+    ])?;
+
+    Ok(RawInputs {
+        returns: returns_df.lazy(),
+        market_caps: mkt_cap_df.lazy(),
+        valuations: Some(fundamentals_df.lazy()),
+        sectors: prepare_sector_data(&sector_labels)?.lazy(),
+    })
+}
+
+/// Prepare one-hot encoded sector data from a `date, symbol, sector` frame.
+fn prepare_sector_data(labels: &DataFrame) -> CliResult<DataFrame> {
+    let sectors: Vec<String> = labels
+        .column("sector")?
+        .str()?
+        .into_iter()
+        .filter_map(|s| s.map(|s| s.to_string()))
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut lf = labels.clone().lazy().select([col("date"), col("symbol"), col("sector")]);
+
+    for sector in &sectors {
+        lf = lf.with_column(
+            when(col("sector").eq(lit(sector.as_str())))
+                .then(lit(1.0))
+                .otherwise(lit(0.0))
+                .alias(format!("sector_{sector}")),
+        );
+    }
+
+    let sector_df = lf.select([col("*").exclude(["sector"])]).collect()?;
+
+    Ok(sector_df)
+}
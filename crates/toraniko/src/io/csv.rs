@@ -0,0 +1,418 @@
+//! CSV and Parquet loader for the `data/` directory layout.
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use polars::prelude::*;
+
+use super::DataError;
+
+/// Asset returns file.
+const RETURNS_FILE: &str = "daily_returns.csv";
+/// Market capitalization file.
+const MARKET_CAPS_FILE: &str = "market_caps.csv";
+/// Sector membership file.
+const SECTORS_FILE: &str = "sectors.csv";
+/// Optional valuation ratio file.
+const VALUATIONS_FILE: &str = "valuations.csv";
+/// Extension tried when a `.csv` input is absent.
+const PARQUET_EXTENSION: &str = "parquet";
+
+/// Validated input frames for factor return estimation.
+///
+/// Every frame has a `date` column of type Date, a `symbol` column, and f64
+/// value columns, with at most one row per `date, symbol`.
+#[derive(Clone)]
+pub struct Dataset {
+    /// | date | symbol | asset_returns |
+    pub returns: LazyFrame,
+    /// | date | symbol | market_cap |
+    pub market_caps: LazyFrame,
+    /// | date | symbol | sector_* | (plus an optional `sector` label column)
+    pub sectors: LazyFrame,
+    /// | date | symbol | valuation ratios... |, if `valuations.csv` exists
+    pub valuations: Option<LazyFrame>,
+}
+
+impl std::fmt::Debug for Dataset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dataset")
+            .field("valuations", &self.valuations.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Load and validate a dataset directory.
+///
+/// Reads `daily_returns.csv`, `market_caps.csv`, `sectors.csv` and, if
+/// present, `valuations.csv`; each file may instead be Parquet with the same
+/// stem (e.g. `daily_returns.parquet`). CSV dates must be `YYYY-MM-DD` and
+/// Parquet dates may also be Date or Datetime columns; all columns other
+/// than `date`, `symbol` and the `sector` label must be numeric and are
+/// cast to f64.
+///
+/// # Arguments
+/// * `dir` - Directory containing the CSV files
+///
+/// # Returns
+/// A [`Dataset`] whose frames can be passed to `ReturnsEstimator::estimate`
+/// together with style scores.
+///
+/// # Errors
+/// Returns `DataError::MissingFile` for a missing required file,
+/// `DataError::MissingColumn` if a required column is absent (including a
+/// sectors file without `sector_*` columns), `DataError::InvalidColumn` or
+/// `DataError::InvalidDates` for values of the wrong type, and
+/// `DataError::DuplicateRows` if a `date, symbol` key repeats.
+pub fn load_dataset(dir: impl AsRef<Path>) -> Result<Dataset, DataError> {
+    let dir = dir.as_ref();
+
+    load_with_sectors(dir, |_| {
+        let sectors = read_validated(&dir.join(SECTORS_FILE), &[])?;
+        if !sectors.get_column_names().iter().any(|c| c.starts_with("sector_")) {
+            return Err(DataError::MissingColumn {
+                file: SECTORS_FILE.to_string(),
+                column: "sector_*".to_string(),
+            });
+        }
+        Ok(sectors.lazy())
+    })
+}
+
+/// Load a dataset directory, taking sectors from a symbol to sector map.
+///
+/// Reads the same files as [`load_dataset`] except `sectors`, which is
+/// replaced by one-hot `sector_*` exposures built from the map for every
+/// `date, symbol` in the returns, so memberships are constant over time.
+///
+/// # Arguments
+/// * `dir` - Directory containing the CSV or Parquet files
+/// * `sector_map` - CSV or Parquet file with `symbol` and `sector` columns
+///
+/// # Returns
+/// A [`Dataset`] whose sector frame also carries the `sector` label.
+///
+/// # Errors
+/// Returns the errors of [`load_dataset`] and [`load_sector_map`], or
+/// `DataError::UnknownSymbol` for the first returns symbol missing from the
+/// map.
+pub fn load_dataset_with_sector_map(
+    dir: impl AsRef<Path>,
+    sector_map: impl AsRef<Path>,
+) -> Result<Dataset, DataError> {
+    let sector_map = load_sector_map(sector_map)?;
+    load_with_sectors(dir.as_ref(), |returns| sector_exposures(returns, &sector_map))
+}
+
+/// Load a symbol to sector map.
+///
+/// # Arguments
+/// * `path` - CSV or Parquet file with `symbol` and `sector` string columns
+///
+/// # Returns
+/// DataFrame with | symbol | sector |, one row per symbol.
+///
+/// # Errors
+/// Returns `DataError::MissingFile` if the file does not exist,
+/// `DataError::MissingColumn` or `DataError::InvalidColumn` for a missing or
+/// non-string column, and `DataError::DuplicateRows` if a symbol is mapped
+/// more than once.
+pub fn load_sector_map(path: impl AsRef<Path>) -> Result<DataFrame, DataError> {
+    let path = path.as_ref();
+    let file = file_name(path);
+    let raw = read_raw(path, &["symbol", "sector"])?;
+
+    for column in ["symbol", "sector"] {
+        let dtype = raw
+            .column(column)
+            .map_err(|_| DataError::MissingColumn { file: file.clone(), column: column.into() })?
+            .dtype();
+        if dtype != &DataType::String {
+            return Err(DataError::InvalidColumn {
+                file,
+                column: column.to_string(),
+                expected: "a string",
+            });
+        }
+    }
+
+    let map = raw.select(["symbol", "sector"])?.drop_nulls::<String>(None)?;
+    let duplicates = map
+        .clone()
+        .lazy()
+        .group_by([col("symbol")])
+        .agg([len().alias("_rows")])
+        .filter(col("_rows").gt(lit(1)))
+        .sort(["symbol"], SortMultipleOptions::default())
+        .collect()?;
+    if duplicates.height() > 0 {
+        let first = duplicates.column("symbol")?.str()?.get(0).unwrap_or("").to_string();
+        return Err(DataError::DuplicateRows { file, count: duplicates.height(), first });
+    }
+
+    Ok(map)
+}
+
+/// Load every file except sectors, which `sectors` builds from the returns.
+fn load_with_sectors(
+    dir: &Path,
+    sectors: impl FnOnce(&DataFrame) -> Result<LazyFrame, DataError>,
+) -> Result<Dataset, DataError> {
+    let returns = read_validated(&dir.join(RETURNS_FILE), &["asset_returns"])?;
+    let market_caps = read_validated(&dir.join(MARKET_CAPS_FILE), &["market_cap"])?;
+    let sectors = sectors(&returns)?;
+
+    let valuations_path = dir.join(VALUATIONS_FILE);
+    let valuations = if resolve(&valuations_path).is_ok() {
+        Some(read_validated(&valuations_path, &[])?.lazy())
+    } else {
+        None
+    };
+
+    Ok(Dataset { returns: returns.lazy(), market_caps: market_caps.lazy(), sectors, valuations })
+}
+
+/// One-hot sector exposures for every `date, symbol` row of `returns`.
+fn sector_exposures(returns: &DataFrame, sector_map: &DataFrame) -> Result<LazyFrame, DataError> {
+    let labeled = returns
+        .clone()
+        .lazy()
+        .select([col("date"), col("symbol")])
+        .join(
+            sector_map.clone().lazy(),
+            [col("symbol")],
+            [col("symbol")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .collect()?;
+
+    let unmapped = labeled.column("sector")?.is_null();
+    if let Some(row) = unmapped.iter().position(|missing| missing == Some(true)) {
+        let symbol = labeled.column("symbol")?.str()?.get(row).unwrap_or("").to_string();
+        return Err(DataError::UnknownSymbol(symbol));
+    }
+
+    // Only sectors with members in the data, so no exposure column is all zero
+    let mut sectors: Vec<String> =
+        labeled.column("sector")?.str()?.iter().flatten().map(str::to_string).collect();
+    sectors.sort_unstable();
+    sectors.dedup();
+    let one_hot: Vec<Expr> = sectors
+        .iter()
+        .map(|sector| {
+            col("sector")
+                .eq(lit(sector.as_str()))
+                .cast(DataType::Float64)
+                .alias(format!("sector_{sector}"))
+        })
+        .collect();
+
+    Ok(labeled.lazy().with_columns(one_hot))
+}
+
+/// Resolve an input path, falling back to the Parquet file with the same stem.
+fn resolve(path: &Path) -> Result<PathBuf, DataError> {
+    let parquet = path.with_extension(PARQUET_EXTENSION);
+    if path.exists() {
+        Ok(path.to_path_buf())
+    } else if parquet.exists() {
+        Ok(parquet)
+    } else {
+        Err(DataError::MissingFile(path.to_path_buf()))
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(String::new, |f| f.to_string_lossy().into_owned())
+}
+
+/// Read a CSV or Parquet file, reading the `string_columns` of a CSV as text.
+fn read_raw(path: &Path, string_columns: &[&str]) -> Result<DataFrame, DataError> {
+    let path = resolve(path)?;
+    if path.extension().is_some_and(|ext| ext == PARQUET_EXTENSION) {
+        return Ok(ParquetReader::new(File::open(&path)?).finish()?);
+    }
+
+    let overwrite = string_columns.iter().map(|name| Field::new((*name).into(), DataType::String));
+    Ok(CsvReadOptions::default()
+        .with_has_header(true)
+        .with_schema_overwrite(Some(Arc::new(Schema::from_iter(overwrite))))
+        .try_into_reader_with_file_path(Some(path))?
+        .finish()?)
+}
+
+/// Read a `date, symbol, ...` file and validate its schema and keys.
+fn read_validated(path: &Path, required: &[&str]) -> Result<DataFrame, DataError> {
+    let raw = read_raw(path, &["date", "symbol"])?;
+    let file = file_name(&resolve(path)?);
+
+    let names: Vec<String> = raw.get_column_names().iter().map(|c| c.to_string()).collect();
+    for column in ["date", "symbol"].iter().chain(required) {
+        if !names.iter().any(|n| n == column) {
+            return Err(DataError::MissingColumn { file, column: (*column).to_string() });
+        }
+    }
+    if raw.column("symbol")?.dtype() != &DataType::String {
+        return Err(DataError::InvalidColumn {
+            file,
+            column: "symbol".to_string(),
+            expected: "a string",
+        });
+    }
+
+    // Value columns must already be numeric; string columns other than the
+    // sector label indicate malformed values
+    let date = match raw.column("date")?.dtype() {
+        DataType::String => col("date").str().to_date(StrptimeOptions {
+            format: Some("%Y-%m-%d".into()),
+            strict: false,
+            ..Default::default()
+        }),
+        DataType::Date | DataType::Datetime(..) => col("date").cast(DataType::Date),
+        _ => {
+            return Err(DataError::InvalidColumn {
+                file,
+                column: "date".to_string(),
+                expected: "a date",
+            });
+        }
+    };
+    let mut casts = vec![date.alias("date")];
+    for name in names.iter().filter(|n| !matches!(n.as_str(), "date" | "symbol" | "sector")) {
+        let dtype = raw.column(name)?.dtype();
+        if !(dtype.is_primitive_numeric() || dtype.is_null()) {
+            return Err(DataError::InvalidColumn {
+                file,
+                column: name.clone(),
+                expected: "numeric",
+            });
+        }
+        casts.push(col(name.as_str()).cast(DataType::Float64));
+    }
+    let df = raw.lazy().with_columns(casts).collect()?;
+
+    let invalid_dates = df.column("date")?.null_count();
+    if invalid_dates > 0 {
+        return Err(DataError::InvalidDates { file, count: invalid_dates });
+    }
+
+    let duplicates = df
+        .clone()
+        .lazy()
+        .group_by([col("date"), col("symbol")])
+        .agg([len().alias("_rows")])
+        .filter(col("_rows").gt(lit(1)))
+        .sort(["date", "symbol"], SortMultipleOptions::default())
+        .collect()?;
+    if duplicates.height() > 0 {
+        let first = format!(
+            "{}, {}",
+            duplicates.column("date")?.get(0)?,
+            duplicates.column("symbol")?.str()?.get(0).unwrap_or("")
+        );
+        return Err(DataError::DuplicateRows { file, count: duplicates.height(), first });
+    }
+
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Write a dataset to a fresh temporary directory.
+    fn write_dataset(name: &str, returns: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("toraniko-io-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(RETURNS_FILE), returns).unwrap();
+        std::fs::write(
+            dir.join(MARKET_CAPS_FILE),
+            "date,symbol,market_cap\n2024-01-02,A,100\n2024-01-02,B,200\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(SECTORS_FILE),
+            "date,symbol,sector,sector_Tech\n2024-01-02,A,Tech,1.0\n2024-01-02,B,Tech,1.0\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn loads_repository_data() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../data");
+        let dataset = load_dataset(dir).unwrap();
+
+        let returns = dataset.returns.collect().unwrap();
+        assert_eq!(returns.column("date").unwrap().dtype(), &DataType::Date);
+        assert_eq!(returns.column("asset_returns").unwrap().dtype(), &DataType::Float64);
+        assert!(dataset.valuations.is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let dir = write_dataset(
+            "dup",
+            "date,symbol,asset_returns\n2024-01-02,A,0.01\n2024-01-02,A,0.02\n",
+        );
+        assert!(matches!(load_dataset(&dir), Err(DataError::DuplicateRows { count: 1, .. })));
+
+        let dir = write_dataset("date", "date,symbol,asset_returns\n01/02/2024,A,0.01\n");
+        assert!(matches!(load_dataset(&dir), Err(DataError::InvalidDates { count: 1, .. })));
+
+        let dir = write_dataset("num", "date,symbol,asset_returns\n2024-01-02,A,n/a\n");
+        assert!(matches!(load_dataset(&dir), Err(DataError::InvalidColumn { .. })));
+
+        let dir =
+            write_dataset("ok", "date,symbol,asset_returns\n2024-01-02,A,1\n2024-01-02,B,2\n");
+        let returns = load_dataset(&dir).unwrap().returns.collect().unwrap();
+        assert_eq!(returns.column("asset_returns").unwrap().dtype(), &DataType::Float64);
+        assert!(matches!(load_dataset(dir.join("missing")), Err(DataError::MissingFile(_))));
+
+        for name in ["dup", "date", "num", "ok"] {
+            let dir =
+                std::env::temp_dir().join(format!("toraniko-io-{name}-{}", std::process::id()));
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn loads_parquet_with_sector_map() {
+        let dir = write_dataset(
+            "map",
+            "date,symbol,asset_returns
+2024-01-02,A,0.01
+2024-01-02,B,0.02
+",
+        );
+        let mut returns = load_dataset(&dir).unwrap().returns.collect().unwrap();
+        std::fs::remove_file(dir.join(RETURNS_FILE)).unwrap();
+        std::fs::remove_file(dir.join(SECTORS_FILE)).unwrap();
+        let file = File::create(dir.join("daily_returns.parquet")).unwrap();
+        ParquetWriter::new(file).finish(&mut returns).unwrap();
+
+        let map_path = dir.join("sector_map.csv");
+        std::fs::write(&map_path, "symbol,sector\nA,Tech\nB,Energy\nC,Utilities\n").unwrap();
+        let dataset = load_dataset_with_sector_map(&dir, &map_path).unwrap();
+        assert_eq!(dataset.returns.collect().unwrap(), returns);
+        let sectors = dataset.sectors.collect().unwrap();
+        assert_eq!(
+            sectors.column("sector_Tech").unwrap().f64().unwrap().to_vec(),
+            [Some(1.0), Some(0.0)]
+        );
+        assert!(sectors.column("sector_Utilities").is_err());
+
+        std::fs::write(&map_path, "symbol,sector\nA,Tech\n").unwrap();
+        let result = load_dataset_with_sector_map(&dir, &map_path);
+        assert!(matches!(result, Err(DataError::UnknownSymbol(symbol)) if symbol == "B"));
+
+        std::fs::write(&map_path, "symbol,sector\nA,Tech\nA,Energy\n").unwrap();
+        assert!(matches!(load_sector_map(&map_path), Err(DataError::DuplicateRows { .. })));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+//! Error types for dataset loading.
+
+use std::path::PathBuf;
+
+/// Errors that can occur while loading or validating a dataset.
+#[derive(Debug, thiserror::Error)]
+pub enum DataError {
+    /// Filesystem error.
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Polars error.
+    #[error("polars error: {0}")]
+    Polars(#[from] polars::error::PolarsError),
+
+    /// Model error.
+    #[error("model error: {0}")]
+    Model(#[from] toraniko_model::ModelError),
+
+    /// Snapshot serialization error.
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Snapshot written with an unsupported format version.
+    #[error("snapshot format version {found} is not supported (expected {supported})")]
+    IncompatibleSnapshot {
+        /// Version found in the file.
+        found: u32,
+        /// Version supported by this build.
+        supported: u32,
+    },
+
+    /// Snapshot whose matrices do not match its factors and symbols.
+    #[error("invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    /// A symbol is not covered by the data.
+    #[error("unknown symbol: {0}")]
+    UnknownSymbol(String),
+
+    /// A required file is missing.
+    #[error("missing file: {}", .0.display())]
+    MissingFile(PathBuf),
+
+    /// A required column is missing from a file.
+    #[error("{file}: missing column {column}")]
+    MissingColumn {
+        /// File name.
+        file: String,
+        /// Column name.
+        column: String,
+    },
+
+    /// A column could not be read with the expected type.
+    #[error("{file}: column {column} is not {expected}")]
+    InvalidColumn {
+        /// File name.
+        file: String,
+        /// Column name.
+        column: String,
+        /// Expected type.
+        expected: &'static str,
+    },
+
+    /// Dates that are missing or not formatted as `YYYY-MM-DD`.
+    #[error("{file}: {count} rows have a missing or invalid date")]
+    InvalidDates {
+        /// File name.
+        file: String,
+        /// Number of offending rows.
+        count: usize,
+    },
+
+    /// Rows sharing the same key (`date, symbol`, or `symbol` in a sector map).
+    #[error("{file}: {count} duplicate keys (first: {first})")]
+    DuplicateRows {
+        /// File name.
+        file: String,
+        /// Number of duplicated keys.
+        count: usize,
+        /// First duplicated key.
+        first: String,
+    },
+}
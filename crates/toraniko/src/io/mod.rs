@@ -0,0 +1,13 @@
+//! Dataset loading and validation.
+
+mod csv;
+pub use csv::{Dataset, load_dataset, load_dataset_with_sector_map, load_sector_map};
+
+mod snapshot;
+pub use snapshot::{RiskModelSnapshot, SNAPSHOT_FORMAT_VERSION, SnapshotFactor, SnapshotMetadata};
+
+mod store;
+pub use store::{Artifact, ArtifactStore, StorageFormat};
+
+mod error;
+pub use error::DataError;
@@ -0,0 +1,403 @@
+//! Versioned on-disk risk model snapshots.
+
+use std::{collections::HashMap, path::Path};
+
+use ndarray::{Array1, Array2};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use toraniko_model::{EstimationFrequency, EstimatorConfig, FactorCovariance, RiskConfig};
+use toraniko_primitives::Date;
+use toraniko_traits::FactorKind;
+
+use super::DataError;
+
+/// Snapshot format version written by this crate.
+///
+/// Bumped whenever a change to [`RiskModelSnapshot`] would make older
+/// snapshots load incorrectly.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// A factor in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFactor {
+    /// Factor name (e.g. `market`, `sector_Technology`, `mom_score`).
+    pub name: String,
+    /// Factor kind.
+    pub kind: FactorKind,
+}
+
+impl SnapshotFactor {
+    /// Create a factor, inferring its kind from the estimator's column naming.
+    #[must_use]
+    pub fn from_name(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let kind = if name == "market" {
+            FactorKind::Market
+        } else if name.starts_with("sector_") {
+            FactorKind::Sector
+        } else if name.starts_with("country_") {
+            FactorKind::Country
+        } else {
+            FactorKind::Style
+        };
+        Self { name, kind }
+    }
+}
+
+/// How a snapshot was produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    /// Factor return estimation configuration.
+    pub estimator: EstimatorConfig,
+    /// Covariance and specific risk configuration.
+    pub risk: RiskConfig,
+    /// Version of the `toraniko` crate that wrote the snapshot.
+    pub code_version: String,
+    /// Number of assets in the snapshot.
+    pub universe_size: usize,
+}
+
+/// Complete risk model as of one date.
+///
+/// Holds everything needed for risk queries: factor exposures per asset, the
+/// factor covariance and specific variances, all per period of the model's
+/// frequency. Snapshots are stored as a single JSON document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskModelSnapshot {
+    /// Format version, see [`SNAPSHOT_FORMAT_VERSION`].
+    pub format_version: u32,
+    /// As-of date.
+    pub date: Date,
+    /// Factors, in the column order of `exposures` and `covariance`.
+    pub factors: Vec<SnapshotFactor>,
+    /// Asset symbols, in the row order of `exposures`.
+    pub symbols: Vec<String>,
+    /// Exposures, one row per symbol and one column per factor.
+    pub exposures: Vec<Vec<f64>>,
+    /// Factor covariance matrix.
+    pub covariance: Vec<Vec<f64>>,
+    /// Specific variance per symbol.
+    pub specific_variance: Vec<f64>,
+    /// Production metadata.
+    pub metadata: SnapshotMetadata,
+}
+
+/// Header read before the full snapshot to check compatibility.
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    format_version: u32,
+}
+
+impl RiskModelSnapshot {
+    /// Assemble a snapshot from model outputs for one date.
+    ///
+    /// Factors follow the covariance order. The market exposure is one for
+    /// every asset; every other factor must be a column of `exposures`. Only
+    /// symbols present in both `exposures` and `specific_variance`, with no
+    /// null factor exposure or specific variance, are kept: a null is never
+    /// read as zero risk.
+    ///
+    /// # Arguments
+    /// * `date` - As-of date
+    /// * `exposures` - DataFrame with | symbol | factor columns... | for `date`
+    /// * `covariance` - Factor covariance
+    /// * `specific_variance` - DataFrame with | symbol | specific_variance |
+    /// * `estimator` - Configuration used to estimate factor returns
+    /// * `risk` - Configuration used for the covariance and specific risk
+    ///
+    /// # Errors
+    /// Returns `DataError::MissingColumn` if a factor has no exposure column,
+    /// or a Polars error if the frames cannot be read.
+    pub fn new(
+        date: Date,
+        exposures: &DataFrame,
+        covariance: &FactorCovariance,
+        specific_variance: &DataFrame,
+        estimator: EstimatorConfig,
+        risk: RiskConfig,
+    ) -> Result<Self, DataError> {
+        let joined = exposures
+            .clone()
+            .lazy()
+            .join(
+                specific_variance.clone().lazy(),
+                [col("symbol")],
+                [col("symbol")],
+                JoinArgs::new(JoinType::Inner),
+            )
+            .sort(["symbol"], SortMultipleOptions::default())
+            .collect()?;
+        let factors: Vec<SnapshotFactor> =
+            covariance.factors().iter().map(SnapshotFactor::from_name).collect();
+        let mut required = vec!["specific_variance".to_string()];
+        for factor in &factors {
+            if joined.column(&factor.name).is_ok() {
+                required.push(factor.name.clone());
+            } else if factor.kind != FactorKind::Market {
+                return Err(DataError::MissingColumn {
+                    file: "exposures".to_string(),
+                    column: factor.name.clone(),
+                });
+            }
+        }
+        let joined = joined.drop_nulls(Some(&required))?;
+        let n = joined.height();
+
+        let mut columns = Vec::with_capacity(factors.len());
+        for factor in &factors {
+            let Ok(values) = joined.column(&factor.name) else {
+                columns.push(vec![1.0; n]);
+                continue;
+            };
+            columns.push(values.cast(&DataType::Float64)?.f64()?.into_no_null_iter().collect());
+        }
+
+        let symbols: Vec<String> =
+            joined.column("symbol")?.str()?.iter().map(|s| s.unwrap_or("").to_string()).collect();
+        let exposures = (0..n).map(|i| columns.iter().map(|c| c[i]).collect()).collect();
+        let specific_variance = joined
+            .column("specific_variance")?
+            .cast(&DataType::Float64)?
+            .f64()?
+            .into_no_null_iter()
+            .collect();
+
+        Ok(Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            date,
+            factors,
+            symbols,
+            exposures,
+            covariance: covariance.matrix().rows().into_iter().map(|r| r.to_vec()).collect(),
+            specific_variance,
+            metadata: SnapshotMetadata {
+                estimator,
+                risk,
+                code_version: env!("CARGO_PKG_VERSION").to_string(),
+                universe_size: n,
+            },
+        })
+    }
+
+    /// Write the snapshot as JSON.
+    ///
+    /// # Errors
+    /// Returns an I/O or serialization error.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DataError> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Self::save`].
+    ///
+    /// # Errors
+    /// Returns `DataError::IncompatibleSnapshot` if the file was written with
+    /// a different format version, `DataError::InvalidSnapshot` if the
+    /// exposures, covariance and specific variances do not fit the factors and
+    /// symbols, or an I/O or deserialization error.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        let contents = std::fs::read_to_string(path)?;
+        let header: SnapshotHeader = serde_json::from_str(&contents)?;
+        if header.format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(DataError::IncompatibleSnapshot {
+                found: header.format_version,
+                supported: SNAPSHOT_FORMAT_VERSION,
+            });
+        }
+        let snapshot: Self = serde_json::from_str(&contents)?;
+        snapshot.check_shapes()?;
+        Ok(snapshot)
+    }
+
+    /// Check that every matrix matches the factors and symbols.
+    fn check_shapes(&self) -> Result<(), DataError> {
+        let (n, k) = (self.symbols.len(), self.factors.len());
+        let invalid = |what: String| Err(DataError::InvalidSnapshot(what));
+        if self.exposures.len() != n {
+            return invalid(format!("{} exposure rows for {n} symbols", self.exposures.len()));
+        }
+        if self.specific_variance.len() != n {
+            return invalid(format!(
+                "{} specific variances for {n} symbols",
+                self.specific_variance.len()
+            ));
+        }
+        if let Some(row) = self.exposures.iter().position(|r| r.len() != k) {
+            return invalid(format!(
+                "exposure row {row} has {} values for {k} factors",
+                self.exposures[row].len()
+            ));
+        }
+        if self.covariance.len() != k || self.covariance.iter().any(|r| r.len() != k) {
+            return invalid(format!("covariance is not {k} x {k}"));
+        }
+        Ok(())
+    }
+
+    /// Model frequency of the covariance and specific variances.
+    #[must_use]
+    pub const fn frequency(&self) -> EstimationFrequency {
+        self.metadata.risk.frequency
+    }
+
+    /// Exposure matrix (n_assets x n_factors).
+    #[must_use]
+    pub fn exposure_matrix(&self) -> Array2<f64> {
+        to_matrix(&self.exposures, self.factors.len())
+    }
+
+    /// Factor covariance matrix (n_factors x n_factors).
+    #[must_use]
+    pub fn covariance_matrix(&self) -> Array2<f64> {
+        to_matrix(&self.covariance, self.factors.len())
+    }
+
+    /// Total variance of a portfolio per period, `w'XFX'w + Σ w²s`.
+    ///
+    /// # Arguments
+    /// * `weights` - Holdings as `(symbol, weight)` pairs
+    ///
+    /// # Errors
+    /// Returns `DataError::UnknownSymbol` if a holding is not in the snapshot.
+    pub fn portfolio_variance(&self, weights: &[(&str, f64)]) -> Result<f64, DataError> {
+        let index: HashMap<&str, usize> =
+            self.symbols.iter().enumerate().map(|(i, s)| (s.as_str(), i)).collect();
+
+        let mut factor_exposure = Array1::<f64>::zeros(self.factors.len());
+        let mut specific = 0.0;
+        for &(symbol, weight) in weights {
+            let &i =
+                index.get(symbol).ok_or_else(|| DataError::UnknownSymbol(symbol.to_string()))?;
+            factor_exposure.scaled_add(weight, &Array1::from(self.exposures[i].clone()));
+            specific += weight * weight * self.specific_variance[i];
+        }
+
+        Ok(factor_exposure.dot(&self.covariance_matrix().dot(&factor_exposure)) + specific)
+    }
+}
+
+fn to_matrix(rows: &[Vec<f64>], ncols: usize) -> Array2<f64> {
+    Array2::from_shape_fn((rows.len(), ncols), |(i, j)| rows[i][j])
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use toraniko_model::factor_covariance;
+
+    use super::*;
+
+    fn snapshot() -> RiskModelSnapshot {
+        let dates: Vec<Date> =
+            (2..=4).map(|d| Date::from_ymd_opt(2024, 1, d).unwrap()).flat_map(|d| [d, d]).collect();
+        let factor_returns = df! {
+            "date" => &dates,
+            "factor" => ["market", "sector_Tech"].repeat(3),
+            "factor_return" => &[0.01, 0.02, -0.01, 0.0, 0.02, -0.01],
+        }
+        .unwrap();
+        let risk = RiskConfig { factor_half_life: 2, ..Default::default() };
+        let covariance = factor_covariance(&factor_returns, &risk).unwrap();
+        let exposures = df! {
+            "symbol" => &["B", "A", "C", "D", "E"],
+            "sector_Tech" => &[Some(0.0), Some(1.0), Some(1.0), None, Some(1.0)],
+        }
+        .unwrap();
+        // D has a null exposure and E a null specific variance: both are dropped
+        let specific = df! {
+            "symbol" => &["A", "B", "D", "E"],
+            "specific_variance" => &[Some(0.0004), Some(0.0009), Some(0.0001), None],
+        }
+        .unwrap();
+
+        RiskModelSnapshot::new(
+            dates[5],
+            &exposures,
+            &covariance,
+            &specific,
+            Default::default(),
+            risk,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_and_risk_query() {
+        let snapshot = snapshot();
+        assert_eq!(snapshot.symbols, ["A", "B"]);
+        assert_eq!(snapshot.factors[1].kind, FactorKind::Sector);
+        assert_eq!(snapshot.metadata.universe_size, 2);
+
+        let path =
+            std::env::temp_dir().join(format!("toraniko-snapshot-{}.json", std::process::id()));
+        snapshot.save(&path).unwrap();
+        let loaded = RiskModelSnapshot::load(&path).unwrap();
+        assert_eq!(loaded, snapshot);
+
+        let root = std::env::temp_dir().join(format!("toraniko-snapshots-{}", std::process::id()));
+        let store = crate::io::ArtifactStore::new(&root);
+        store.write_snapshot(&snapshot).unwrap();
+        assert_eq!(store.read_snapshot(snapshot.date).unwrap(), snapshot);
+        let missing = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        assert!(matches!(store.read_snapshot(missing), Err(DataError::MissingFile(_))));
+        std::fs::remove_dir_all(root).unwrap();
+
+        // A has exposure (1, 1): variance is the sum of the covariance plus specific
+        let f = snapshot.covariance_matrix();
+        assert_relative_eq!(
+            loaded.portfolio_variance(&[("A", 1.0)]).unwrap(),
+            f.sum() + 0.0004,
+            epsilon = 1e-15
+        );
+        assert!(matches!(
+            loaded.portfolio_variance(&[("C", 1.0)]),
+            Err(DataError::UnknownSymbol(_))
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatched_shapes() {
+        let path = std::env::temp_dir()
+            .join(format!("toraniko-snapshot-shape-{}.json", std::process::id()));
+        let check = |edit: fn(&mut RiskModelSnapshot)| {
+            let mut snapshot = snapshot();
+            edit(&mut snapshot);
+            snapshot.save(&path).unwrap();
+            RiskModelSnapshot::load(&path)
+        };
+
+        assert!(check(|_| {}).is_ok());
+        let edits: [fn(&mut RiskModelSnapshot); 4] = [
+            |s| {
+                s.exposures[1].pop();
+            },
+            |s| s.specific_variance.push(0.1),
+            |s| s.symbols.push("C".to_string()),
+            |s| {
+                s.covariance.pop();
+            },
+        ];
+        for edit in edits {
+            assert!(matches!(check(edit), Err(DataError::InvalidSnapshot(_))));
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_format_versions() {
+        let mut snapshot = snapshot();
+        snapshot.format_version = SNAPSHOT_FORMAT_VERSION + 1;
+        let path =
+            std::env::temp_dir().join(format!("toraniko-snapshot-v2-{}.json", std::process::id()));
+        snapshot.save(&path).unwrap();
+
+        let result = RiskModelSnapshot::load(&path);
+        assert!(matches!(result, Err(DataError::IncompatibleSnapshot { found: 2, supported: 1 })));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,488 @@
+//! Date-partitioned Parquet and Arrow IPC storage for model outputs.
+
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use polars::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+use toraniko_model::{EstimationFrequency, EstimatorConfig, FactorCovariance, RiskState};
+use toraniko_primitives::Date;
+
+use super::{DataError, RiskModelSnapshot};
+
+/// Prefix of partition directory names, followed by the ISO date.
+const PARTITION_PREFIX: &str = "date=";
+
+/// File holding the persisted [`RiskState`].
+const RISK_STATE_FILE: &str = "risk_state.json";
+
+/// File holding the [`EstimatorConfig`] the stored outputs were estimated with.
+const ESTIMATOR_CONFIG_FILE: &str = "estimator.json";
+
+/// Directory holding one [`RiskModelSnapshot`] per date.
+const SNAPSHOT_DIR: &str = "snapshots";
+
+/// On-disk file format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageFormat {
+    /// Apache Parquet.
+    #[default]
+    Parquet,
+    /// Arrow IPC (Feather v2).
+    Ipc,
+}
+
+impl StorageFormat {
+    /// File extension without the dot.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Ipc => "arrow",
+        }
+    }
+}
+
+/// Model output stored by an [`ArtifactStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Artifact {
+    /// | date | factor | factor_return |
+    FactorReturns,
+    /// | date | symbol | residual_return |
+    Residuals,
+    /// | date | symbol | exposures... |
+    Exposures,
+    /// | date | factor_1 | factor_2 | covariance |
+    Covariance,
+}
+
+impl Artifact {
+    /// Directory name of the artifact.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FactorReturns => "factor_returns",
+            Self::Residuals => "residuals",
+            Self::Exposures => "exposures",
+            Self::Covariance => "covariance",
+        }
+    }
+}
+
+/// Date-partitioned store of model artifacts.
+///
+/// Each artifact lives under `root/<artifact>/date=YYYY-MM-DD/data.<ext>`,
+/// with the `date` column kept in the files. Writing replaces the partitions
+/// of the dates being written and leaves every other date untouched, so a
+/// daily job appends new dates and a rerun of the same date is idempotent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactStore {
+    root: PathBuf,
+    format: StorageFormat,
+}
+
+impl ArtifactStore {
+    /// Create a Parquet store rooted at `root`.
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), format: StorageFormat::Parquet }
+    }
+
+    /// Set the file format.
+    #[must_use]
+    pub const fn with_format(mut self, format: StorageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Root directory of the store.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// File format of the store.
+    #[must_use]
+    pub const fn format(&self) -> StorageFormat {
+        self.format
+    }
+
+    fn partition_file(&self, artifact: Artifact, date: Date) -> PathBuf {
+        self.root
+            .join(artifact.name())
+            .join(format!("{PARTITION_PREFIX}{date}"))
+            .join(format!("data.{}", self.format.extension()))
+    }
+
+    /// Write a frame, one partition per distinct `date`.
+    ///
+    /// Each partition is written to a temporary file and renamed into place,
+    /// so readers never observe a partially written date. Rows with a null
+    /// `date` are rejected before anything is written.
+    ///
+    /// # Returns
+    /// The dates written, in order.
+    ///
+    /// # Errors
+    /// Returns `DataError::MissingColumn` if `df` has no Date-typed `date`
+    /// column, `DataError::InvalidDates` if any `date` is null, or an I/O or
+    /// Polars error if writing fails.
+    pub fn write(&self, artifact: Artifact, df: &DataFrame) -> Result<Vec<Date>, DataError> {
+        let missing_date = || DataError::MissingColumn {
+            file: artifact.name().to_string(),
+            column: "date".to_string(),
+        };
+        let dates = df.column("date").map_err(|_| missing_date())?;
+        if dates.dtype() != &DataType::Date {
+            return Err(missing_date());
+        }
+        if dates.null_count() > 0 {
+            return Err(DataError::InvalidDates {
+                file: artifact.name().to_string(),
+                count: dates.null_count(),
+            });
+        }
+
+        let mut written = Vec::new();
+        for mut partition in df.partition_by_stable(["date"], true)? {
+            let date = partition
+                .column("date")?
+                .date()?
+                .as_date_iter()
+                .next()
+                .flatten()
+                .expect("null dates are rejected above");
+            let path = self.partition_file(artifact, date);
+            let dir = path.parent().expect("partition file has a parent");
+            std::fs::create_dir_all(dir)?;
+
+            let tmp = dir.join(format!(".data.{}.tmp", self.format.extension()));
+            let file = File::create(&tmp)?;
+            match self.format {
+                StorageFormat::Parquet => {
+                    ParquetWriter::new(file).finish(&mut partition)?;
+                }
+                StorageFormat::Ipc => IpcWriter::new(file).finish(&mut partition)?,
+            }
+            std::fs::rename(&tmp, &path)?;
+            written.push(date);
+        }
+
+        written.sort();
+        Ok(written)
+    }
+
+    /// Dates stored for an artifact, in order.
+    ///
+    /// # Errors
+    /// Returns an I/O error if the artifact directory cannot be listed.
+    pub fn dates(&self, artifact: Artifact) -> Result<Vec<Date>, DataError> {
+        let dir = self.root.join(artifact.name());
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut dates = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            let date = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(PARTITION_PREFIX))
+                .and_then(|d| d.parse::<Date>().ok());
+            if let Some(date) = date.filter(|d| self.partition_file(artifact, *d).exists()) {
+                dates.push(date);
+            }
+        }
+        dates.sort();
+        Ok(dates)
+    }
+
+    /// Lazily scan the partitions of an artifact in `start..=end`.
+    ///
+    /// Either bound may be omitted. Only partitions in the range are opened.
+    /// Partitions are concatenated diagonally, so wide artifacts whose columns
+    /// change between dates (e.g. a new style or sector) fill the missing
+    /// columns with nulls.
+    ///
+    /// # Errors
+    /// Returns `DataError::MissingFile` if no partition falls in the range,
+    /// or an I/O or Polars error if a partition cannot be scanned.
+    pub fn scan(
+        &self,
+        artifact: Artifact,
+        start: Option<Date>,
+        end: Option<Date>,
+    ) -> Result<LazyFrame, DataError> {
+        let hive_options = polars::io::HiveOptions { enabled: Some(false), ..Default::default() };
+        let frames = self
+            .dates(artifact)?
+            .into_iter()
+            .filter(|d| start.is_none_or(|s| *d >= s) && end.is_none_or(|e| *d <= e))
+            .map(|date| {
+                let path = self.partition_file(artifact, date);
+                match self.format {
+                    StorageFormat::Parquet => LazyFrame::scan_parquet(
+                        path,
+                        ScanArgsParquet {
+                            hive_options: hive_options.clone(),
+                            ..Default::default()
+                        },
+                    ),
+                    StorageFormat::Ipc => LazyFrame::scan_ipc(
+                        path,
+                        ScanArgsIpc { hive_options: hive_options.clone(), ..Default::default() },
+                    ),
+                }
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+
+        if frames.is_empty() {
+            return Err(DataError::MissingFile(self.root.join(artifact.name())));
+        }
+        Ok(concat_lf_diagonal(frames, UnionArgs::default())?)
+    }
+
+    /// Write the factor covariance estimated as of `date`.
+    ///
+    /// # Errors
+    /// Returns an error if the covariance cannot be converted or written.
+    pub fn write_covariance(
+        &self,
+        date: Date,
+        covariance: &FactorCovariance,
+    ) -> Result<(), DataError> {
+        let mut df = covariance.to_frame()?;
+        df.insert_column(0, Column::new("date".into(), vec![date; df.height()]))?;
+        self.write(Artifact::Covariance, &df)?;
+        Ok(())
+    }
+
+    /// Read the factor covariance stored for `date`.
+    ///
+    /// # Errors
+    /// Returns `DataError::MissingFile` if no covariance is stored for the
+    /// date, or an error if it cannot be read or rebuilt.
+    pub fn read_covariance(
+        &self,
+        date: Date,
+        frequency: EstimationFrequency,
+    ) -> Result<FactorCovariance, DataError> {
+        let df = self.scan(Artifact::Covariance, Some(date), Some(date))?.collect()?;
+        Ok(FactorCovariance::from_frame(&df, frequency)?)
+    }
+
+    /// Persist the risk state for the next incremental update.
+    ///
+    /// # Errors
+    /// Returns an I/O or serialization error.
+    pub fn write_risk_state(&self, state: &RiskState) -> Result<(), DataError> {
+        self.write_json(RISK_STATE_FILE, state)
+    }
+
+    /// Read the persisted risk state, or `None` before the first run.
+    ///
+    /// # Errors
+    /// Returns an I/O or deserialization error.
+    pub fn read_risk_state(&self) -> Result<Option<RiskState>, DataError> {
+        self.read_json(RISK_STATE_FILE)
+    }
+
+    /// Record the estimator configuration used for the stored outputs.
+    ///
+    /// # Errors
+    /// Returns an I/O or serialization error.
+    pub fn write_estimator_config(&self, config: &EstimatorConfig) -> Result<(), DataError> {
+        self.write_json(ESTIMATOR_CONFIG_FILE, config)
+    }
+
+    /// Read the recorded estimator configuration, or `None` if none was written.
+    ///
+    /// # Errors
+    /// Returns an I/O or deserialization error.
+    pub fn read_estimator_config(&self) -> Result<Option<EstimatorConfig>, DataError> {
+        self.read_json(ESTIMATOR_CONFIG_FILE)
+    }
+
+    fn snapshot_file(&self, date: Date) -> PathBuf {
+        self.root.join(SNAPSHOT_DIR).join(format!("{date}.json"))
+    }
+
+    /// Write a risk model snapshot under `root/snapshots/YYYY-MM-DD.json`.
+    ///
+    /// # Returns
+    /// The path written.
+    ///
+    /// # Errors
+    /// Returns an I/O or serialization error.
+    pub fn write_snapshot(&self, snapshot: &RiskModelSnapshot) -> Result<PathBuf, DataError> {
+        let path = self.snapshot_file(snapshot.date);
+        std::fs::create_dir_all(self.root.join(SNAPSHOT_DIR))?;
+        snapshot.save(&path)?;
+        Ok(path)
+    }
+
+    /// Read the risk model snapshot stored for `date`.
+    ///
+    /// # Errors
+    /// Returns `DataError::MissingFile` if no snapshot is stored for the date,
+    /// or the errors of [`RiskModelSnapshot::load`].
+    pub fn read_snapshot(&self, date: Date) -> Result<RiskModelSnapshot, DataError> {
+        let path = self.snapshot_file(date);
+        if !path.exists() {
+            return Err(DataError::MissingFile(path));
+        }
+        RiskModelSnapshot::load(path)
+    }
+
+    /// Write a JSON file in the store root via a temporary file and rename.
+    fn write_json(&self, name: &str, value: &impl Serialize) -> Result<(), DataError> {
+        std::fs::create_dir_all(&self.root)?;
+        let tmp = self.root.join(format!(".{name}.tmp"));
+        serde_json::to_writer(std::io::BufWriter::new(File::create(&tmp)?), value)?;
+        std::fs::rename(tmp, self.root.join(name))?;
+        Ok(())
+    }
+
+    fn read_json<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, DataError> {
+        let path = self.root.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use toraniko_model::{RiskConfig, factor_covariance};
+
+    use super::*;
+
+    fn date(d: u32) -> Date {
+        Date::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    fn factor_returns(days: &[u32], value: f64) -> DataFrame {
+        let dates: Vec<Date> = days.iter().flat_map(|&d| [date(d), date(d)]).collect();
+        df! {
+            "date" => &dates,
+            "factor" => ["market", "sector_Tech"].repeat(days.len()),
+            "factor_return" => (0..dates.len()).map(|i| value * i as f64).collect::<Vec<_>>(),
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn append_and_scan_range() {
+        for format in [StorageFormat::Parquet, StorageFormat::Ipc] {
+            let root = std::env::temp_dir().join(format!(
+                "toraniko-store-{}-{}",
+                format.extension(),
+                std::process::id()
+            ));
+            let store = ArtifactStore::new(&root).with_format(format);
+
+            store.write(Artifact::FactorReturns, &factor_returns(&[2, 3], 0.01)).unwrap();
+            // Appending a new date and rewriting an existing one
+            let written =
+                store.write(Artifact::FactorReturns, &factor_returns(&[3, 4], 0.02)).unwrap();
+            assert_eq!(written, vec![date(3), date(4)]);
+            assert_eq!(store.dates(Artifact::FactorReturns).unwrap(), [2, 3, 4].map(date));
+
+            let range = store
+                .scan(Artifact::FactorReturns, Some(date(3)), None)
+                .unwrap()
+                .sort(["date", "factor"], SortMultipleOptions::default())
+                .collect()
+                .unwrap();
+            assert_eq!(range.height(), 4);
+            let values: Vec<f64> =
+                range.column("factor_return").unwrap().f64().unwrap().into_no_null_iter().collect();
+            assert_eq!(values, vec![0.0, 0.02, 0.04, 0.06]);
+            assert!(store.scan(Artifact::Residuals, None, None).is_err());
+
+            std::fs::remove_dir_all(root).unwrap();
+        }
+    }
+
+    #[test]
+    fn scan_unions_changing_columns() {
+        let root = std::env::temp_dir().join(format!("toraniko-store-wide-{}", std::process::id()));
+        let store = ArtifactStore::new(&root);
+
+        let first = df! {
+            "date" => [date(2)],
+            "symbol" => ["A"],
+            "mom_score" => [1.0],
+        }
+        .unwrap();
+        let second = df! {
+            "date" => [date(3)],
+            "symbol" => ["A"],
+            "mom_score" => [2.0],
+            "val_score" => [0.5],
+        }
+        .unwrap();
+        store.write(Artifact::Exposures, &first).unwrap();
+        store.write(Artifact::Exposures, &second).unwrap();
+
+        let scanned = store
+            .scan(Artifact::Exposures, None, None)
+            .unwrap()
+            .sort(["date"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+        let values: Vec<Option<f64>> =
+            scanned.column("val_score").unwrap().f64().unwrap().into_iter().collect();
+        assert_eq!(values, vec![None, Some(0.5)]);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn null_dates_error() {
+        let root = std::env::temp_dir().join(format!("toraniko-store-null-{}", std::process::id()));
+        let store = ArtifactStore::new(&root);
+
+        let df = df! {
+            "date" => [Some(date(2)), None],
+            "factor" => ["market", "market"],
+            "factor_return" => [0.01, 0.02],
+        }
+        .unwrap();
+        let result = store.write(Artifact::FactorReturns, &df);
+        assert!(matches!(result, Err(DataError::InvalidDates { count: 1, .. })));
+        assert!(store.dates(Artifact::FactorReturns).unwrap().is_empty());
+    }
+
+    #[test]
+    fn covariance_round_trip() {
+        let root = std::env::temp_dir().join(format!("toraniko-store-cov-{}", std::process::id()));
+        let store = ArtifactStore::new(&root);
+
+        let mut returns = factor_returns(&[2, 3, 4], 0.01);
+        returns
+            .with_column(Column::new("factor_return".into(), [0.01, 0.02, -0.01, 0.0, 0.02, -0.01]))
+            .unwrap();
+        let covariance = factor_covariance(&returns, &RiskConfig::default()).unwrap();
+
+        store.write_covariance(date(4), &covariance).unwrap();
+        let restored = store.read_covariance(date(4), EstimationFrequency::Daily).unwrap();
+        assert_eq!(restored, covariance);
+
+        assert!(store.read_risk_state().unwrap().is_none());
+        let mut state = RiskState::new(RiskConfig::default()).unwrap();
+        state.update_factors(&returns).unwrap();
+        store.write_risk_state(&state).unwrap();
+        assert_eq!(store.read_risk_state().unwrap(), Some(state));
+
+        assert!(store.read_estimator_config().unwrap().is_none());
+        let config = EstimatorConfig { winsor_factor: Some(0.05), ..Default::default() };
+        store.write_estimator_config(&config).unwrap();
+        assert_eq!(store.read_estimator_config().unwrap(), Some(config));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -22,10 +22,19 @@ pub use toraniko_model as model;
 #[cfg(feature = "utils")]
 #[doc(inline)]
 pub use toraniko_utils as utils;
+#[cfg(feature = "styles")]
+#[doc(inline)]
+pub use toraniko_styles as styles;
+
+#[cfg(feature = "io")]
+pub mod io;
+
 // CLI feature dependencies - these are used by the analyze binary, not the library itself
 #[cfg(feature = "cli")]
 use chrono as _;
 #[cfg(feature = "cli")]
+use clap as _;
+#[cfg(feature = "cli")]
 use factors as _;
 #[cfg(feature = "cli")]
 use polars as _;
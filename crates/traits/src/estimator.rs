@@ -50,6 +50,14 @@ pub enum EstimatorError {
     /// Linear algebra error.
     #[error("linear algebra error: {0}")]
     LinearAlgebra(String),
+
+    /// Input frames that do not match the expected schema.
+    #[error("invalid input data: {0}")]
+    InvalidInput(String),
+
+    /// Invalid factor exposures in the input data.
+    #[error("invalid exposures: {0}")]
+    InvalidExposures(String),
 }
 
 impl EstimatorError {
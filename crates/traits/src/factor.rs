@@ -4,14 +4,18 @@
 pub use factors::{
     ConfigurableFactor, DataFrequency, Factor, FactorCategory, FactorConfig, FactorError,
 };
+use serde::{Deserialize, Serialize};
 
 /// The kind of factor in the model.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FactorKind {
     /// Market factor (beta to overall market).
     Market,
     /// Sector/industry classification factor.
     Sector,
+    /// Country factor in a global model.
+    Country,
     /// Style/characteristic factor (momentum, value, size, etc.).
     Style,
 }
@@ -21,6 +25,7 @@ impl std::fmt::Display for FactorKind {
         match self {
             Self::Market => write!(f, "market"),
             Self::Sector => write!(f, "sector"),
+            Self::Country => write!(f, "country"),
             Self::Style => write!(f, "style"),
         }
     }
@@ -62,6 +67,7 @@ mod tests {
     fn factor_kind_display() {
         assert_eq!(FactorKind::Market.to_string(), "market");
         assert_eq!(FactorKind::Sector.to_string(), "sector");
+        assert_eq!(FactorKind::Country.to_string(), "country");
         assert_eq!(FactorKind::Style.to_string(), "style");
     }
 }
@@ -0,0 +1,127 @@
+//! Calendar alignment and stale observation checks.
+
+use polars::prelude::*;
+use toraniko_primitives::{Calendar, Date};
+
+/// Reindex a `date, symbol` frame to the business days of a calendar.
+///
+/// Each symbol gets one row per business day in `start..=end` between its
+/// first and last observation, so listings and delistings do not produce
+/// missing rows. Rows on non-business days or outside the range are dropped.
+/// An `observed` column is false for rows added by the reindex, whose other
+/// columns are null.
+///
+/// # Arguments
+/// * `df` - LazyFrame with | date | symbol | ... |, where date is a Date column
+/// * `calendar` - Trading calendar
+/// * `start` - First date of the grid
+/// * `end` - Last date of the grid
+///
+/// # Returns
+/// LazyFrame with | date | symbol | observed | ... |, sorted by symbol and date.
+pub fn align_to_calendar(df: LazyFrame, calendar: &Calendar, start: Date, end: Date) -> LazyFrame {
+    let dates =
+        DataFrame::new(vec![Column::new("date".into(), calendar.business_days(start, end))])
+            .expect("single column frame")
+            .lazy();
+
+    let ranges = df
+        .clone()
+        .group_by([col("symbol")])
+        .agg([col("date").min().alias("_first_date"), col("date").max().alias("_last_date")]);
+
+    dates
+        .cross_join(ranges, None)
+        .filter(col("date").gt_eq(col("_first_date")).and(col("date").lt_eq(col("_last_date"))))
+        .select([col("date"), col("symbol")])
+        .join(
+            df.with_column(lit(true).alias("observed")),
+            [col("date"), col("symbol")],
+            [col("date"), col("symbol")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .with_column(col("observed").fill_null(lit(false)))
+        .sort(["symbol", "date"], SortMultipleOptions::default())
+}
+
+/// Flag observations whose value has not changed for too long.
+///
+/// Within each symbol, ordered by date, a row is flagged in a boolean
+/// `stale` column when its value equals that of more than `max_unchanged`
+/// immediately preceding rows (e.g. a price that stopped updating). Null
+/// values are never flagged.
+///
+/// # Arguments
+/// * `df` - LazyFrame with | date | symbol | `value_col` | ... |
+/// * `value_col` - Column to check (typically a price)
+/// * `max_unchanged` - Number of repeats allowed before a value is stale
+///
+/// # Returns
+/// LazyFrame with all input rows plus `stale`, sorted by symbol and date.
+pub fn flag_stale_observations(df: LazyFrame, value_col: &str, max_unchanged: u32) -> LazyFrame {
+    let value = col(value_col);
+    let changed = value.clone().neq_missing(value.clone().shift(lit(1))).over([col("symbol")]);
+
+    df.sort(["symbol", "date"], SortMultipleOptions::default())
+        .with_column(changed.cast(DataType::UInt32).alias("_run"))
+        .with_column(col("_run").cum_sum(false).over([col("symbol")]))
+        .with_column(
+            (col("_run").cum_count(false).cast(DataType::Int64) - lit(1i64))
+                .over([col("symbol"), col("_run")])
+                .alias("_repeats"),
+        )
+        .with_column(
+            value
+                .is_not_null()
+                .and(col("_repeats").gt(lit(i64::from(max_unchanged))))
+                .alias("stale"),
+        )
+        .drop(["_run", "_repeats"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(d: u32) -> Date {
+        Date::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn aligns_to_business_days() {
+        let calendar = Calendar::weekdays().with_holidays([date(1)]);
+        let df = df! {
+            "date" => [2, 3, 6, 8, 4, 5].map(date),
+            "symbol" => &["A", "A", "A", "A", "B", "B"],
+            "price" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = align_to_calendar(df, &calendar, date(1), date(31)).collect().unwrap();
+
+        // A: Jan 2-8 without the weekend (Jan 6 is a Saturday and is dropped)
+        let dates: Vec<Date> =
+            result.column("date").unwrap().date().unwrap().as_date_iter().flatten().collect();
+        assert_eq!(dates, [2, 3, 4, 5, 8, 4, 5].map(date));
+        let observed: Vec<bool> =
+            result.column("observed").unwrap().bool().unwrap().into_no_null_iter().collect();
+        assert_eq!(observed, vec![true, true, false, false, true, true, true]);
+    }
+
+    #[test]
+    fn flags_stale_prices() {
+        let df = df! {
+            "date" => &[1, 2, 3, 4, 5, 6, 1, 2],
+            "symbol" => &["A", "A", "A", "A", "A", "A", "B", "B"],
+            "price" => &[Some(1.0), Some(1.0), Some(1.0), Some(1.0), Some(2.0), None, None, None],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = flag_stale_observations(df, "price", 2).collect().unwrap();
+        let stale: Vec<bool> =
+            result.column("stale").unwrap().bool().unwrap().into_no_null_iter().collect();
+        assert_eq!(stale, vec![false, false, false, true, false, false, false, false]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Point-in-time as-of joins.
+
+use polars::prelude::*;
+
+/// Configuration for [`point_in_time_join`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsOfConfig {
+    /// Column in the fundamentals frame holding the announcement date.
+    pub announcement_col: String,
+    /// Days after the announcement before the data is treated as known.
+    pub publication_lag_days: i32,
+    /// Maximum age in days of a matched record, measured from when it became
+    /// available (None for no limit).
+    pub max_staleness_days: Option<i32>,
+}
+
+impl Default for AsOfConfig {
+    fn default() -> Self {
+        Self {
+            announcement_col: "announcement_date".to_string(),
+            publication_lag_days: 1,
+            max_staleness_days: Some(365),
+        }
+    }
+}
+
+/// Attach the latest available fundamentals to each row of a daily grid.
+///
+/// A fundamentals record becomes available `publication_lag_days` after its
+/// announcement date. Each `date, symbol` row of the grid is matched, per
+/// symbol, to the most recent record available on or before that date, so
+/// descriptors built from the result never use data before it was published.
+/// Records older than `max_staleness_days` are not matched.
+///
+/// # Arguments
+/// * `grid` - LazyFrame with | date | symbol | ... |, e.g. daily returns
+/// * `fundamentals` - LazyFrame with | symbol | `announcement_col` | fields... |
+/// * `config` - Announcement column, publication lag and staleness limit
+///
+/// # Returns
+/// LazyFrame with every grid row and the matched fundamentals columns (null
+/// where nothing is available). Fundamentals columns that clash with grid
+/// columns get a `_right` suffix.
+pub fn point_in_time_join(
+    grid: LazyFrame,
+    fundamentals: LazyFrame,
+    config: &AsOfConfig,
+) -> LazyFrame {
+    let announced = config.announcement_col.as_str();
+
+    let grid = grid
+        .with_column(col("date").cast(DataType::Int32).alias("_day"))
+        .sort(["_day"], SortMultipleOptions::default());
+    let fundamentals = fundamentals
+        .filter(col(announced).is_not_null())
+        .with_column(
+            (col(announced).cast(DataType::Int32) + lit(config.publication_lag_days))
+                .alias("_available"),
+        )
+        .sort(["_available"], SortMultipleOptions::default());
+
+    let options = AsOfOptions {
+        strategy: AsofStrategy::Backward,
+        tolerance: config.max_staleness_days.map(AnyValue::Int32),
+        left_by: Some(vec!["symbol".into()]),
+        right_by: Some(vec!["symbol".into()]),
+        allow_eq: true,
+        ..Default::default()
+    };
+
+    grid.join(
+        fundamentals,
+        [col("_day")],
+        [col("_available")],
+        JoinArgs::new(JoinType::AsOf(options)),
+    )
+    .select([col("*").exclude(["_day", "_available"])])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn respects_lag_and_staleness() {
+        let grid = df! {
+            "date" => [2, 3, 4, 10, 2, 3].map(date),
+            "symbol" => &["A", "A", "A", "A", "B", "B"],
+        }
+        .unwrap()
+        .lazy();
+        let fundamentals = df! {
+            "symbol" => &["A", "A", "B"],
+            "announcement_date" => [1, 3, 2].map(date),
+            "book_value" => &[10.0, 12.0, 50.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = AsOfConfig {
+            publication_lag_days: 1,
+            max_staleness_days: Some(4),
+            ..Default::default()
+        };
+        let result = point_in_time_join(grid, fundamentals, &config)
+            .sort(["symbol", "date"], SortMultipleOptions::default())
+            .collect()
+            .unwrap();
+
+        let values: Vec<Option<f64>> =
+            result.column("book_value").unwrap().f64().unwrap().iter().collect();
+        // A: Jan 1 report known from Jan 2; Jan 3 report known from Jan 4 and
+        // stale by Jan 10. B: Jan 2 report not yet known on Jan 2.
+        assert_eq!(values, vec![Some(10.0), Some(10.0), Some(12.0), None, None, Some(50.0)]);
+        assert!(result.column("_day").is_err());
+    }
+}
@@ -1,7 +1,77 @@
 //! Feature filling utilities.
 
+use std::collections::BTreeMap;
+
 use polars::prelude::*;
 
+use crate::UtilsError;
+
+/// Configuration for [`fill_features_with`].
+///
+/// Strategies are applied in order: non-finite values are converted to null,
+/// then forward filled within each partition, then filled with the
+/// cross-sectional median of their group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillConfig {
+    /// Forward fill within each partition.
+    pub forward: bool,
+    /// Maximum age in days of a forward-filled value (None for no limit).
+    ///
+    /// Requires `sort_col` to be a date (or integer day) column.
+    pub max_staleness_days: Option<i32>,
+    /// Fill remaining nulls with the median over `sort_col` and this column
+    /// (e.g. sector), or None to disable.
+    pub cross_sectional_group: Option<String>,
+}
+
+impl Default for FillConfig {
+    fn default() -> Self {
+        Self { forward: true, max_staleness_days: None, cross_sectional_group: None }
+    }
+}
+
+/// Per-column counts of values changed by [`fill_features_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FillCount {
+    /// NaN or infinite values converted to null.
+    pub non_finite: usize,
+    /// Nulls filled by forward fill.
+    pub forward: usize,
+    /// Nulls filled with the cross-sectional median.
+    pub cross_sectional: usize,
+    /// Nulls remaining after all strategies.
+    pub remaining: usize,
+}
+
+impl FillCount {
+    /// Total number of filled cells.
+    #[must_use]
+    pub const fn filled(&self) -> usize {
+        self.forward + self.cross_sectional
+    }
+}
+
+/// Report of the cells filled per column.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FillReport {
+    /// Counts keyed by column name.
+    pub columns: BTreeMap<String, FillCount>,
+}
+
+impl FillReport {
+    /// Total number of filled cells across all columns.
+    #[must_use]
+    pub fn total_filled(&self) -> usize {
+        self.columns.values().map(FillCount::filled).sum()
+    }
+}
+
+/// Cast to float and convert NaN/Inf to null.
+fn finite_or_null(feat: &str) -> Expr {
+    let values = col(feat).cast(DataType::Float64);
+    when(values.clone().is_finite()).then(values).otherwise(lit(NULL).cast(DataType::Float64))
+}
+
 /// Fill missing values in feature columns.
 ///
 /// Casts to float, converts NaN/Inf to null, then forward fills
@@ -26,8 +96,7 @@ pub fn fill_features(
 
     for &feat in features {
         lf = lf.with_column(
-            col(feat)
-                .cast(DataType::Float64)
+            finite_or_null(feat)
                 // Forward fill within partition
                 .forward_fill(None)
                 .over([col(over_col)])
@@ -38,6 +107,101 @@ pub fn fill_features(
     lf
 }
 
+/// Fill missing values with configurable strategies and report what was filled.
+///
+/// # Arguments
+/// * `df` - Input LazyFrame
+/// * `features` - Column names to fill
+/// * `sort_col` - Column to sort by (typically "date")
+/// * `over_col` - Column to partition by (typically "symbol")
+/// * `config` - Fill strategies
+///
+/// # Returns
+/// Tuple of (DataFrame sorted by `sort_col` with filled features, per-column report).
+///
+/// # Errors
+/// Returns `UtilsError::MissingColumn` if a feature or the cross-sectional
+/// group column is missing, or `UtilsError::Polars` if evaluation fails.
+pub fn fill_features_with(
+    df: LazyFrame,
+    features: &[&str],
+    sort_col: &str,
+    over_col: &str,
+    config: &FillConfig,
+) -> Result<(DataFrame, FillReport), UtilsError> {
+    let sort_options = SortMultipleOptions::new().with_maintain_order(true);
+    let df = df.sort([sort_col], sort_options).collect()?;
+
+    let required = features.iter().copied().chain(config.cross_sectional_group.as_deref());
+    for name in required {
+        if df.column(name).is_err() {
+            return Err(UtilsError::MissingColumn(name.to_string()));
+        }
+    }
+
+    let null_counts = |df: &DataFrame| -> Result<Vec<usize>, UtilsError> {
+        features.iter().map(|&f| Ok(df.column(f)?.null_count())).collect()
+    };
+
+    let before = null_counts(&df)?;
+    let mut lf = df.lazy();
+    for &feat in features {
+        lf = lf.with_column(finite_or_null(feat).alias(feat));
+    }
+    let df = lf.collect()?;
+    let cleaned = null_counts(&df)?;
+
+    let mut lf = df.lazy();
+    if config.forward {
+        for &feat in features {
+            let filled = col(feat).forward_fill(None).over([col(over_col)]);
+            let filled = match config.max_staleness_days {
+                Some(max_days) => {
+                    let day = col(sort_col).cast(DataType::Int32);
+                    let last_seen = when(col(feat).is_not_null())
+                        .then(day.clone())
+                        .otherwise(lit(NULL).cast(DataType::Int32))
+                        .forward_fill(None)
+                        .over([col(over_col)]);
+                    when((day - last_seen).lt_eq(lit(max_days)))
+                        .then(filled)
+                        .otherwise(lit(NULL).cast(DataType::Float64))
+                }
+                None => filled,
+            };
+            lf = lf.with_column(filled.alias(feat));
+        }
+    }
+    let df = lf.collect()?;
+    let forward = null_counts(&df)?;
+
+    let mut lf = df.lazy();
+    if let Some(group) = &config.cross_sectional_group {
+        for &feat in features {
+            let median = col(feat).median().over([col(sort_col), col(group.as_str())]);
+            lf = lf.with_column(col(feat).fill_null(median).alias(feat));
+        }
+    }
+    let df = lf.collect()?;
+    let remaining = null_counts(&df)?;
+
+    let columns = features
+        .iter()
+        .enumerate()
+        .map(|(i, &feat)| {
+            let count = FillCount {
+                non_finite: cleaned[i] - before[i],
+                forward: cleaned[i] - forward[i],
+                cross_sectional: forward[i] - remaining[i],
+                remaining: remaining[i],
+            };
+            (feat.to_string(), count)
+        })
+        .collect();
+
+    Ok((df, FillReport { columns }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +268,72 @@ mod tests {
         assert_eq!(result.column("val1").unwrap().f64().unwrap().get(1), Some(1.0));
         assert_eq!(result.column("val2").unwrap().f64().unwrap().get(2), Some(2.0));
     }
+
+    #[test]
+    fn fill_features_non_finite() {
+        let df = df! {
+            "date" => &[1, 2, 3],
+            "symbol" => &["A", "A", "B"],
+            "value" => &[1.0, f64::NAN, f64::INFINITY],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = fill_features(df, &["value"], "date", "symbol").collect().unwrap();
+        let values: Vec<Option<f64>> =
+            result.column("value").unwrap().f64().unwrap().into_iter().collect();
+
+        assert_eq!(values, vec![Some(1.0), Some(1.0), None]);
+    }
+
+    #[test]
+    fn fill_with_staleness_and_median() {
+        let df = df! {
+            "date" => &[1, 1, 1, 2, 2, 2, 5, 5, 5],
+            "symbol" => &["A", "B", "C", "A", "B", "C", "A", "B", "C"],
+            "sector" => &["X", "X", "X", "X", "X", "X", "X", "X", "X"],
+            "value" => &[
+                Some(1.0), Some(2.0), None,
+                None, Some(4.0), Some(f64::NAN),
+                None, Some(6.0), Some(8.0),
+            ],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = FillConfig {
+            max_staleness_days: Some(2),
+            cross_sectional_group: Some("sector".to_string()),
+            ..Default::default()
+        };
+        let (result, report) =
+            fill_features_with(df, &["value"], "date", "symbol", &config).unwrap();
+        let values: Vec<Option<f64>> =
+            result.column("value").unwrap().f64().unwrap().into_iter().collect();
+
+        // A on date 2 is 1 day stale and forward filled; A on date 5 is 4 days
+        // stale and takes the sector median of B and C. C on dates 1 and 2 has
+        // no history and takes the sector median.
+        assert_eq!(
+            values,
+            vec![
+                Some(1.0),
+                Some(2.0),
+                Some(1.5),
+                Some(1.0),
+                Some(4.0),
+                Some(2.5),
+                Some(7.0),
+                Some(6.0),
+                Some(8.0),
+            ]
+        );
+
+        let count = report.columns["value"];
+        assert_eq!(
+            count,
+            FillCount { non_finite: 1, forward: 1, cross_sectional: 3, remaining: 0 }
+        );
+        assert_eq!(report.total_filled(), 4);
+    }
 }
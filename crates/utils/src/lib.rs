@@ -4,13 +4,25 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 mod fill;
-pub use fill::fill_features;
+pub use fill::{FillConfig, FillCount, FillReport, fill_features, fill_features_with};
 
 mod smooth;
-pub use smooth::smooth_features;
+pub use smooth::{SmoothConfig, SmoothMethod, smooth_features, smooth_features_with};
 
 mod rank;
-pub use rank::top_n_by_group;
+pub use rank::{RankConfig, RankCutoff, TiesPolicy, rank_by_group, top_n_by_group};
+
+mod asof;
+pub use asof::{AsOfConfig, point_in_time_join};
+
+mod returns;
+pub use returns::{
+    ReturnFrequency, ReturnKind, ReturnsConfig, compound_returns, flag_suspicious_returns,
+    total_returns,
+};
+
+mod align;
+pub use align::{align_to_calendar, flag_stale_observations};
 
 mod error;
 pub use error::UtilsError;
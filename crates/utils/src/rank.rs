@@ -2,8 +2,114 @@
 
 use polars::prelude::*;
 
+/// How tied values are ranked within a group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TiesPolicy {
+    /// Tied values share the lowest rank; the next rank skips (1, 2, 2, 4).
+    Min,
+    /// Tied values share a rank; the next rank does not skip (1, 2, 2, 3).
+    Dense,
+    /// Tied values get distinct ranks in order of appearance (1, 2, 3, 4).
+    #[default]
+    Ordinal,
+}
+
+impl From<TiesPolicy> for RankMethod {
+    fn from(ties: TiesPolicy) -> Self {
+        match ties {
+            TiesPolicy::Min => Self::Min,
+            TiesPolicy::Dense => Self::Dense,
+            TiesPolicy::Ordinal => Self::Ordinal,
+        }
+    }
+}
+
+/// Number of rows per group selected by the rank mask.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankCutoff {
+    /// Rows ranked `1..=n`.
+    Count(u32),
+    /// Rows ranked within the top fraction of non-null rows in the group,
+    /// rounded up (e.g. `0.1` selects the top decile).
+    Fraction(f64),
+}
+
+/// Configuration for [`rank_by_group`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankConfig {
+    /// Rank the largest value first.
+    pub descending: bool,
+    /// Ties policy.
+    pub ties: TiesPolicy,
+    /// Cutoff for the `rank_mask` column.
+    pub cutoff: RankCutoff,
+}
+
+impl Default for RankConfig {
+    fn default() -> Self {
+        Self { descending: true, ties: TiesPolicy::Ordinal, cutoff: RankCutoff::Count(1) }
+    }
+}
+
+/// Rank rows within each group and mark the rows inside the cutoff.
+///
+/// Adds a `rank` column (1 = best), replacing any input column of that name,
+/// and a boolean `rank_mask` column that is true only for rows within
+/// `config.cutoff`. Rows with a null ranking value get a null rank and are
+/// never selected. Under the `Min` and `Dense` ties policies every row tied at
+/// the cutoff is selected, so a group may select more than `n` rows.
+///
+/// # Arguments
+/// * `df` - Input LazyFrame
+/// * `rank_var` - Column to rank by
+/// * `group_vars` - Columns to group by
+/// * `config` - Direction, ties policy and cutoff
+///
+/// # Returns
+/// LazyFrame with all input rows plus `rank` and `rank_mask`.
+pub fn rank_by_group(
+    df: LazyFrame,
+    rank_var: &str,
+    group_vars: &[&str],
+    config: &RankConfig,
+) -> LazyFrame {
+    rank_into(df, rank_var, group_vars, config, "rank")
+}
+
+/// [`rank_by_group`] writing the rank to `rank_col`.
+fn rank_into(
+    df: LazyFrame,
+    rank_var: &str,
+    group_vars: &[&str],
+    config: &RankConfig,
+    rank_col: &str,
+) -> LazyFrame {
+    let group_exprs: Vec<Expr> = group_vars.iter().map(|&c| col(c)).collect();
+    let over = |e: Expr| if group_exprs.is_empty() { e } else { e.over(group_exprs.clone()) };
+
+    let options = RankOptions { method: config.ties.into(), descending: config.descending };
+    let rank = over(col(rank_var).rank(options, None)).cast(DataType::UInt32);
+
+    let selected = match config.cutoff {
+        RankCutoff::Count(n) => col(rank_col).lt_eq(lit(n)),
+        // rank <= ceil(count * p)  <=>  rank - 1 < count * p for integer ranks
+        RankCutoff::Fraction(p) => {
+            (col(rank_col).cast(DataType::Float64) - lit(1.0))
+                .lt(over(col(rank_var).count()).cast(DataType::Float64) * lit(p))
+        }
+    };
+
+    df.with_column(rank.alias(rank_col))
+        .with_column(selected.fill_null(lit(false)).alias("rank_mask"))
+}
+
 /// Select top N rows in each group by a ranking variable.
 ///
+/// Ranks descending with ordinal ties, so exactly `min(n, group size)` rows
+/// with a non-null ranking value are selected per group. Use
+/// [`rank_by_group`] for other ties policies, ascending order or fractional
+/// cutoffs.
+///
 /// # Arguments
 /// * `df` - Input LazyFrame
 /// * `n` - Number of top rows to select per group
@@ -20,37 +126,20 @@ pub fn top_n_by_group(
     group_vars: &[&str],
     filter: bool,
 ) -> LazyFrame {
-    let group_exprs: Vec<Expr> = group_vars.iter().map(|&c| col(c)).collect();
+    let config = RankConfig { cutoff: RankCutoff::Count(n), ..Default::default() };
+    let ranked = rank_into(df, rank_var, group_vars, &config, "_rank").drop(["_rank"]);
 
-    // Use a descending sort-based rank approximation
-    let ranked = df.with_column(
-        // Create a descending rank using argsort
-        col(rank_var)
-            .sort_by([col(rank_var)], SortMultipleOptions::new().with_order_descending(true))
-            .over(group_exprs.clone())
-            .alias("_sorted_val"),
-    );
-
-    // Simple approach: sort by rank_var descending within groups and take top n
-    let sort_options = SortMultipleOptions::new().with_order_descending(true);
-
-    if filter {
-        // Sort and use head within each group
-        ranked
-            .sort([rank_var], sort_options)
-            .group_by(group_exprs)
-            .head(Some(n as usize))
-            .drop(["_sorted_val"])
-    } else {
-        // Add a mask column indicating top N
-        ranked.with_column(lit(true).alias("rank_mask")).drop(["_sorted_val"])
-    }
+    if filter { ranked.filter(col("rank_mask")).drop(["rank_mask"]) } else { ranked }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn masked(df: DataFrame) -> Vec<bool> {
+        df.column("rank_mask").unwrap().bool().unwrap().into_no_null_iter().collect()
+    }
+
     #[test]
     fn top_n_by_group_filter() {
         let df = df! {
@@ -65,6 +154,9 @@ mod tests {
 
         // Should have 2 rows per date (top 2 by value)
         assert_eq!(result.height(), 4);
+        let symbols: Vec<&str> =
+            result.column("symbol").unwrap().str().unwrap().into_no_null_iter().collect();
+        assert_eq!(symbols, vec!["C", "D", "C", "D"]);
     }
 
     #[test]
@@ -81,6 +173,66 @@ mod tests {
 
         // Should have all 4 rows with rank_mask column
         assert_eq!(result.height(), 4);
-        assert!(result.column("rank_mask").is_ok());
+        assert_eq!(masked(result), vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn top_n_by_group_keeps_rank_column() {
+        let df = df! {
+            "date" => &[1, 1, 1],
+            "rank" => &[7_u32, 8, 9],
+            "value" => &[10.0, 30.0, 20.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = top_n_by_group(df, 1, "value", &["date"], false).collect().unwrap();
+
+        let ranks: Vec<u32> =
+            result.column("rank").unwrap().u32().unwrap().into_no_null_iter().collect();
+        assert_eq!(ranks, vec![7, 8, 9]);
+        assert_eq!(masked(result), vec![false, true, false]);
+    }
+
+    #[test]
+    fn rank_ties_policies() {
+        let df = df! {
+            "date" => &[1, 1, 1, 1],
+            "value" => &[40.0, 30.0, 30.0, 10.0],
+        }
+        .unwrap();
+
+        let ranks = |ties: TiesPolicy| {
+            let config = RankConfig { ties, cutoff: RankCutoff::Count(2), ..Default::default() };
+            let result =
+                rank_by_group(df.clone().lazy(), "value", &["date"], &config).collect().unwrap();
+            let ranks: Vec<u32> =
+                result.column("rank").unwrap().u32().unwrap().into_no_null_iter().collect();
+            (ranks, masked(result))
+        };
+
+        assert_eq!(ranks(TiesPolicy::Min), (vec![1, 2, 2, 4], vec![true, true, true, false]));
+        assert_eq!(ranks(TiesPolicy::Dense), (vec![1, 2, 2, 3], vec![true, true, true, false]));
+        assert_eq!(ranks(TiesPolicy::Ordinal), (vec![1, 2, 3, 4], vec![true, true, false, false]));
+    }
+
+    #[test]
+    fn rank_ascending_fraction() {
+        let df = df! {
+            "date" => &[1, 1, 1, 1, 1, 2, 2],
+            "value" => &[Some(5.0), Some(1.0), Some(3.0), Some(2.0), None, Some(1.0), Some(2.0)],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = RankConfig {
+            descending: false,
+            cutoff: RankCutoff::Fraction(0.5),
+            ..Default::default()
+        };
+        let result = rank_by_group(df, "value", &["date"], &config).collect().unwrap();
+
+        // Date 1 has 4 non-null values -> 2 selected; date 2 has 2 -> 1 selected
+        assert_eq!(masked(result), vec![false, true, false, true, false, true, false]);
     }
 }
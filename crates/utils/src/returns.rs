@@ -0,0 +1,248 @@
+//! Returns computation from prices and corporate actions.
+
+use polars::prelude::*;
+
+/// Return convention.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReturnKind {
+    /// Simple returns, `P_t / P_{t-1} - 1`.
+    #[default]
+    Simple,
+    /// Log returns, `ln(P_t / P_{t-1})`.
+    Log,
+}
+
+/// Frequency of compounded returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnFrequency {
+    /// ISO calendar weeks.
+    Weekly,
+    /// Calendar months.
+    Monthly,
+}
+
+/// Configuration for [`total_returns`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReturnsConfig {
+    /// Raw (unadjusted) close price column.
+    pub price_col: String,
+    /// Cash dividend per share on the ex-date, in post-split shares (None if absent).
+    pub dividend_col: Option<String>,
+    /// Split factor on the ex-date as new shares per old share, e.g. 2.0 for a
+    /// 2-for-1 split (None if absent).
+    pub split_col: Option<String>,
+    /// Return convention.
+    pub kind: ReturnKind,
+}
+
+impl Default for ReturnsConfig {
+    fn default() -> Self {
+        Self {
+            price_col: "close".to_string(),
+            dividend_col: Some("dividend".to_string()),
+            split_col: Some("split_factor".to_string()),
+            kind: ReturnKind::Simple,
+        }
+    }
+}
+
+/// Compute daily total returns from raw prices, dividends and splits.
+///
+/// The simple total return on day t is `s_t * (P_t + D_t) / P_{t-1} - 1`,
+/// where `s_t` is the split factor and `D_t` the dividend on day t. Missing
+/// dividends count as zero and missing split factors as one. The first
+/// observation of each symbol has a null return.
+///
+/// # Arguments
+/// * `prices` - LazyFrame with | date | symbol | price | dividend | split |
+/// * `config` - Column names and return convention
+///
+/// # Returns
+/// LazyFrame with | date | symbol | asset_returns |, sorted by symbol and date.
+pub fn total_returns(prices: LazyFrame, config: &ReturnsConfig) -> LazyFrame {
+    let price = col(config.price_col.as_str()).cast(DataType::Float64);
+    let dividend = config
+        .dividend_col
+        .as_deref()
+        .map_or_else(|| lit(0.0), |c| col(c).cast(DataType::Float64).fill_null(lit(0.0)));
+    let split = config
+        .split_col
+        .as_deref()
+        .map_or_else(|| lit(1.0), |c| col(c).cast(DataType::Float64).fill_null(lit(1.0)));
+
+    let previous = price.clone().shift(lit(1)).over([col("symbol")]);
+    let gross = split * (price + dividend) / previous;
+    let returns = match config.kind {
+        ReturnKind::Simple => gross - lit(1.0),
+        ReturnKind::Log => gross.log(std::f64::consts::E),
+    };
+
+    prices.sort(["symbol", "date"], SortMultipleOptions::default()).select([
+        col("date"),
+        col("symbol"),
+        returns.alias("asset_returns"),
+    ])
+}
+
+/// Compound daily returns to weekly or monthly returns per symbol.
+///
+/// Simple returns are compounded geometrically and log returns are summed.
+/// Each period is labeled with its last observed date. Null daily returns
+/// are skipped.
+///
+/// # Arguments
+/// * `returns` - LazyFrame with | date | symbol | `returns_col` |
+/// * `returns_col` - Daily return column
+/// * `frequency` - Target frequency
+/// * `kind` - Convention of `returns_col`
+///
+/// # Returns
+/// LazyFrame with | date | symbol | `returns_col` | and one row per symbol
+/// and period, sorted by symbol and date.
+pub fn compound_returns(
+    returns: LazyFrame,
+    returns_col: &str,
+    frequency: ReturnFrequency,
+    kind: ReturnKind,
+) -> LazyFrame {
+    let (outer, inner) = match frequency {
+        ReturnFrequency::Weekly => (col("date").dt().iso_year(), col("date").dt().week()),
+        ReturnFrequency::Monthly => (col("date").dt().year(), col("date").dt().month()),
+    };
+    let compounded = match kind {
+        ReturnKind::Simple => (lit(1.0) + col(returns_col)).product() - lit(1.0),
+        ReturnKind::Log => col(returns_col).sum(),
+    };
+
+    returns
+        .filter(col(returns_col).is_not_null())
+        .with_columns([outer.alias("_period_outer"), inner.alias("_period_inner")])
+        .group_by([col("symbol"), col("_period_outer"), col("_period_inner")])
+        .agg([col("date").max(), compounded.alias(returns_col)])
+        .select([col("date"), col("symbol"), col(returns_col)])
+        .sort(["symbol", "date"], SortMultipleOptions::default())
+}
+
+/// Flag returns that should be reviewed before estimation.
+///
+/// Adds a boolean `suspicious_return` column that is true where the return
+/// exceeds `max_abs` in absolute value or is NaN/infinite. Null returns are
+/// not flagged.
+///
+/// # Arguments
+/// * `returns` - LazyFrame containing `returns_col`
+/// * `returns_col` - Return column to check
+/// * `max_abs` - Largest plausible absolute return (e.g. 1.0 for 100%)
+///
+/// # Returns
+/// LazyFrame with all input rows plus `suspicious_return`.
+pub fn flag_suspicious_returns(returns: LazyFrame, returns_col: &str, max_abs: f64) -> LazyFrame {
+    let r = col(returns_col).cast(DataType::Float64);
+    let flagged =
+        r.clone().is_finite().not().or(r.clone().gt(lit(max_abs))).or(r.lt(lit(-max_abs)));
+    returns.with_column(flagged.fill_null(lit(false)).alias("suspicious_return"))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn returns_of(df: &DataFrame, name: &str) -> Vec<Option<f64>> {
+        df.column(name).unwrap().f64().unwrap().iter().collect()
+    }
+
+    #[test]
+    fn total_returns_with_corporate_actions() {
+        let dates: Vec<NaiveDate> =
+            (1..=4).map(|d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap()).collect();
+        let prices = df! {
+            "date" => &dates,
+            "symbol" => &["A", "A", "A", "A"],
+            "close" => &[100.0, 102.0, 49.0, 48.0],
+            "dividend" => &[None, None, None, Some(1.0)],
+            "split_factor" => &[None, None, Some(2.0), None],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = total_returns(prices.clone(), &ReturnsConfig::default()).collect().unwrap();
+        let r = returns_of(&result, "asset_returns");
+        assert!(r[0].is_none());
+        assert_relative_eq!(r[1].unwrap(), 0.02, epsilon = 1e-12);
+        assert_relative_eq!(r[2].unwrap(), 98.0 / 102.0 - 1.0, epsilon = 1e-12);
+        assert_relative_eq!(r[3].unwrap(), 0.0, epsilon = 1e-12);
+
+        let config = ReturnsConfig { kind: ReturnKind::Log, ..Default::default() };
+        let result = total_returns(prices, &config).collect().unwrap();
+        assert_relative_eq!(
+            returns_of(&result, "asset_returns")[1].unwrap(),
+            1.02_f64.ln(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn compound_to_monthly() {
+        let dates: Vec<NaiveDate> = [(1, 30), (1, 31), (2, 1), (2, 2)]
+            .iter()
+            .map(|&(m, d)| NaiveDate::from_ymd_opt(2024, m, d).unwrap())
+            .collect();
+        let daily = df! {
+            "date" => &dates,
+            "symbol" => &["A", "A", "A", "A"],
+            "asset_returns" => &[Some(0.1), Some(-0.1), None, Some(0.05)],
+        }
+        .unwrap()
+        .lazy();
+
+        let monthly = compound_returns(
+            daily.clone(),
+            "asset_returns",
+            ReturnFrequency::Monthly,
+            ReturnKind::Simple,
+        )
+        .collect()
+        .unwrap();
+        assert_eq!(monthly.height(), 2);
+        let r = returns_of(&monthly, "asset_returns");
+        assert_relative_eq!(r[0].unwrap(), 1.1 * 0.9 - 1.0, epsilon = 1e-12);
+        assert_relative_eq!(r[1].unwrap(), 0.05, epsilon = 1e-12);
+        // Months are labeled with their last trading date
+        let expected = Column::new("date".into(), [dates[1], dates[3]]);
+        assert!(monthly.column("date").unwrap().equals(&expected));
+
+        let weekly =
+            compound_returns(daily, "asset_returns", ReturnFrequency::Weekly, ReturnKind::Log)
+                .collect()
+                .unwrap();
+        // Jan 30 - Feb 2 2024 is a single ISO week
+        assert_eq!(weekly.height(), 1);
+        assert_relative_eq!(
+            returns_of(&weekly, "asset_returns")[0].unwrap(),
+            0.05,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn flags_suspicious_returns() {
+        let df = df! {
+            "asset_returns" => &[Some(0.05), Some(1.5), Some(-1.2), Some(f64::NAN), None],
+        }
+        .unwrap()
+        .lazy();
+
+        let result = flag_suspicious_returns(df, "asset_returns", 1.0).collect().unwrap();
+        let flags: Vec<bool> = result
+            .column("suspicious_return")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(flags, vec![false, true, true, true, false]);
+    }
+}
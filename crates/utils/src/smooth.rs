@@ -1,34 +1,158 @@
 //! Feature smoothing utilities.
 
 use polars::prelude::*;
+use toraniko_math::exp_weights;
 
-/// Smooth features using a mean within partitions.
+use crate::UtilsError;
+
+/// Weighting scheme used by [`smooth_features_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmoothMethod {
+    /// Equal-weighted trailing mean.
+    #[default]
+    Simple,
+    /// Exponentially weighted trailing mean with the given half-life in periods.
+    Exponential {
+        /// Half-life in periods.
+        half_life: usize,
+    },
+}
+
+/// Configuration for trailing feature smoothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmoothConfig {
+    /// Number of trailing observations in the window, including the current one.
+    pub window: usize,
+    /// Minimum number of non-null observations required for a value.
+    pub min_periods: usize,
+    /// Weighting scheme.
+    pub method: SmoothMethod,
+}
+
+impl SmoothConfig {
+    /// Equal-weighted window requiring a full window of observations.
+    #[must_use]
+    pub const fn simple(window: usize) -> Self {
+        Self { window, min_periods: window, method: SmoothMethod::Simple }
+    }
+
+    /// Exponentially weighted window requiring a full window of observations.
+    #[must_use]
+    pub const fn exponential(window: usize, half_life: usize) -> Self {
+        Self { window, min_periods: window, method: SmoothMethod::Exponential { half_life } }
+    }
+
+    /// Set the minimum number of observations.
+    #[must_use]
+    pub const fn with_min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods;
+        self
+    }
+}
+
+impl Default for SmoothConfig {
+    fn default() -> Self {
+        Self::simple(5)
+    }
+}
+
+/// Smooth features with a trailing rolling mean within partitions.
 ///
-/// This is a simplified smoothing that computes the mean of each feature
-/// within each partition group. For true rolling windows within groups,
-/// consider using polars' rolling window expressions directly.
+/// Each value is the mean of the current and previous `window_size - 1`
+/// observations of the same partition, ordered by `sort_col`. Rows without a
+/// full window are null. Only past observations are used.
 ///
 /// # Arguments
 /// * `df` - Input LazyFrame
 /// * `features` - Column names to smooth
 /// * `sort_col` - Column to sort by (typically "date")
 /// * `over_col` - Column to partition by (typically "symbol")
-/// * `_window_size` - Window size (reserved for future use)
+/// * `window_size` - Number of trailing observations
 ///
 /// # Returns
-/// LazyFrame with smoothed features.
+/// LazyFrame with smoothed features, sorted by `sort_col`.
 pub fn smooth_features(
     df: LazyFrame,
     features: &[&str],
     sort_col: &str,
     over_col: &str,
-    _window_size: usize,
+    window_size: usize,
+) -> LazyFrame {
+    smooth(df, features, sort_col, over_col, &SmoothConfig::simple(window_size))
+}
+
+/// Smooth features with a configurable trailing window within partitions.
+///
+/// Exponential weights come from [`exp_weights`], with the most recent
+/// observation weighted highest. Windows with fewer than `min_periods`
+/// non-null observations produce null; partial windows are averaged over the
+/// observations present.
+///
+/// # Arguments
+/// * `df` - Input LazyFrame
+/// * `features` - Column names to smooth
+/// * `sort_col` - Column to sort by (typically "date")
+/// * `over_col` - Column to partition by (typically "symbol")
+/// * `config` - Window, minimum periods and weighting scheme
+///
+/// # Returns
+/// LazyFrame with smoothed features, sorted by `sort_col`.
+///
+/// # Errors
+/// Returns `UtilsError::InvalidParameter` if an exponential half-life is zero.
+pub fn smooth_features_with(
+    df: LazyFrame,
+    features: &[&str],
+    sort_col: &str,
+    over_col: &str,
+    config: &SmoothConfig,
+) -> Result<LazyFrame, UtilsError> {
+    if matches!(config.method, SmoothMethod::Exponential { half_life: 0 }) {
+        return Err(UtilsError::InvalidParameter("half_life must be positive".to_string()));
+    }
+    Ok(smooth(df, features, sort_col, over_col, config))
+}
+
+fn smooth(
+    df: LazyFrame,
+    features: &[&str],
+    sort_col: &str,
+    over_col: &str,
+    config: &SmoothConfig,
 ) -> LazyFrame {
     let sort_options = SortMultipleOptions::new().with_maintain_order(true);
     let mut lf = df.sort([sort_col], sort_options);
 
+    let window = config.window.max(1);
+    let min_periods = config.min_periods.clamp(1, window);
+
     for &feat in features {
-        lf = lf.with_column(col(feat).mean().over([col(over_col)]).alias(feat));
+        let values = col(feat).cast(DataType::Float64);
+        let smoothed = match config.method {
+            SmoothMethod::Simple => values.rolling_mean(RollingOptionsFixedWindow {
+                window_size: window,
+                min_periods,
+                ..Default::default()
+            }),
+            SmoothMethod::Exponential { half_life } => {
+                // Weights are applied oldest first within the window
+                let weights: Vec<f64> =
+                    exp_weights(window, half_life).iter().rev().copied().collect();
+                let options = |weights: Option<Vec<f64>>| RollingOptionsFixedWindow {
+                    window_size: window,
+                    min_periods: 1,
+                    weights,
+                    ..Default::default()
+                };
+                // Normalize by the weight of the observations actually present
+                let present = values.clone().is_not_null().cast(DataType::Float64);
+                let count = present.clone().rolling_sum(options(None));
+                let mean = values.fill_null(lit(0.0)).rolling_sum(options(Some(weights.clone())))
+                    / present.rolling_sum(options(Some(weights)));
+                when(count.gt_eq(lit(min_periods as f64))).then(mean).otherwise(lit(NULL))
+            }
+        };
+        lf = lf.with_column(smoothed.over([col(over_col)]).alias(feat));
     }
 
     lf
@@ -36,8 +160,14 @@ pub fn smooth_features(
 
 #[cfg(test)]
 mod tests {
+    use approx::assert_relative_eq;
+
     use super::*;
 
+    fn values(df: &DataFrame) -> Vec<Option<f64>> {
+        df.column("value").unwrap().f64().unwrap().iter().collect()
+    }
+
     #[test]
     fn smooth_features_basic() {
         let df = df! {
@@ -50,18 +180,16 @@ mod tests {
 
         let result = smooth_features(df, &["value"], "date", "symbol", 3).collect().unwrap();
 
-        // Mean of [1,2,3,4,5] = 3.0
-        let values: Vec<f64> =
-            result.column("value").unwrap().f64().unwrap().into_no_null_iter().collect();
-        assert_eq!(values.len(), 5);
+        // Trailing 3-period mean, null until the window is full
+        assert_eq!(values(&result), vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
     }
 
     #[test]
     fn smooth_features_multiple_symbols() {
         let df = df! {
-            "date" => &[1, 2, 1, 2],
+            "date" => &[2, 1, 2, 1],
             "symbol" => &["A", "A", "B", "B"],
-            "value" => &[10.0, 20.0, 100.0, 200.0],
+            "value" => &[20.0, 10.0, 200.0, 100.0],
         }
         .unwrap()
         .lazy();
@@ -70,5 +198,77 @@ mod tests {
 
         // Each symbol should be smoothed independently
         assert_eq!(result.height(), 4);
+        assert_eq!(values(&result), vec![None, None, Some(15.0), Some(150.0)]);
+    }
+
+    #[test]
+    fn smooth_features_no_lookahead() {
+        let df = df! {
+            "date" => &[1, 2, 3, 4],
+            "symbol" => &["A", "A", "A", "A"],
+            "value" => &[1.0, 2.0, 3.0, 1000.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = SmoothConfig::simple(3).with_min_periods(1);
+        let result = smooth_features_with(df, &["value"], "date", "symbol", &config)
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        // A shock on the last date does not affect earlier values
+        assert_eq!(values(&result)[..3], [Some(1.0), Some(1.5), Some(2.0)]);
+    }
+
+    #[test]
+    fn smooth_features_exponential() {
+        let df = df! {
+            "date" => &[1, 2, 3],
+            "symbol" => &["A", "A", "A"],
+            "value" => &[Some(1.0), None, Some(4.0)],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = SmoothConfig::exponential(2, 1).with_min_periods(1);
+        let result = smooth_features_with(df, &["value"], "date", "symbol", &config)
+            .unwrap()
+            .collect()
+            .unwrap();
+        let smoothed = values(&result);
+
+        // Half-life 1: the current observation has twice the weight of the previous one
+        assert_relative_eq!(smoothed[0].unwrap(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(smoothed[1].unwrap(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(smoothed[2].unwrap(), 4.0, epsilon = 1e-12);
+
+        let df = df! {
+            "date" => &[1, 2],
+            "symbol" => &["A", "A"],
+            "value" => &[1.0, 4.0],
+        }
+        .unwrap()
+        .lazy();
+        let result = smooth_features_with(df, &["value"], "date", "symbol", &config)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_relative_eq!(values(&result)[1].unwrap(), (4.0 * 2.0 + 1.0) / 3.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn smooth_features_zero_half_life_errors() {
+        let df = df! {
+            "date" => &[1, 2],
+            "symbol" => &["A", "A"],
+            "value" => &[1.0, 4.0],
+        }
+        .unwrap()
+        .lazy();
+
+        let config = SmoothConfig::exponential(2, 0);
+        let result = smooth_features_with(df, &["value"], "date", "symbol", &config);
+        assert!(matches!(result, Err(UtilsError::InvalidParameter(_))));
     }
 }